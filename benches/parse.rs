@@ -0,0 +1,109 @@
+//! Parse throughput of table representations on large generated
+//! expressions, run with `cargo bench`.
+
+use std::time::{Duration, Instant};
+
+use lr0_gen::{
+    Algo, CompressedTable, DenseTable, Grammar, Semantics, StackAutomata, StoredTable, Table,
+};
+
+// only counts reductions so the time is spent in the table and the stack
+struct Count(usize);
+
+impl Semantics for Count {
+    type Value = ();
+
+    fn on_shift(&mut self, _: char, _: usize) {}
+
+    fn on_reduce(&mut self, _: usize, _: char, _: Vec<()>) {
+        self.0 += 1;
+    }
+}
+
+// random expression over `a`, digits, + * and parentheses, fixed seed
+fn expression(len: usize, digits: bool) -> String {
+    let mut seed: u64 = 0x2545_f491_4f6c_dd1d;
+    let mut next = move |n: u64| {
+        seed ^= seed << 13;
+        seed ^= seed >> 7;
+        seed ^= seed << 17;
+        seed % n
+    };
+    let mut res = String::new();
+    let mut open = 0;
+    while res.len() < len {
+        if next(8) == 0 && open < 30 {
+            res.push('(');
+            open += 1;
+            continue;
+        }
+        match digits && next(2) == 0 {
+            true => res.push(char::from(b'0' + next(10) as u8)),
+            false => res.push('a'),
+        }
+        while open > 0 && next(4) == 0 {
+            res.push(')');
+            open -= 1;
+        }
+        res.push(if next(2) == 0 { '+' } else { '*' });
+    }
+    res.push('a');
+    res.push_str(&")".repeat(open));
+    res
+}
+
+fn measure(table: &dyn Table, input: &str) -> Duration {
+    let mut best = Duration::MAX;
+    for _ in 0..5 {
+        let start = Instant::now();
+        let mut autom = StackAutomata::with_semantics(table, input, Count(0));
+        autom.run().unwrap();
+        best = best.min(start.elapsed());
+    }
+    best
+}
+
+fn main() {
+    let grammars = [
+        ("example", include_str!("../examples/example.gram"), false),
+        ("better", include_str!("../examples/better.gram"), true),
+    ];
+    println!(
+        "{:<8} {:<6} {:<11} {:>10} {:>9}",
+        "grammar", "algo", "table", "MB/s", "speedup"
+    );
+    for (name, text, digits) in grammars {
+        let g = Grammar::from_text(text).unwrap();
+        let input = expression(1 << 20, digits);
+        for algo in [Algo::SLR1, Algo::LALR1, Algo::LR1] {
+            let table = algo.build(&g);
+            let tables: [(&str, Box<dyn Table>); 3] = [
+                ("stored", Box::new(StoredTable::new(table.as_ref()))),
+                ("compressed", Box::new(CompressedTable::new(table.as_ref()))),
+                ("dense", Box::new(DenseTable::new(table.as_ref()))),
+            ];
+            let base = measure(table.as_ref(), &input);
+            let mb = |t: Duration| input.len() as f64 / t.as_secs_f64() / 1e6;
+            println!(
+                "{:<8} {:<6} {:<11} {:>10.1} {:>9}",
+                name,
+                algo.to_string(),
+                table.name(),
+                mb(base),
+                "1.00x"
+            );
+            for (kind, t) in &tables {
+                let time = measure(t.as_ref(), &input);
+                let speedup = base.as_secs_f64() / time.as_secs_f64();
+                println!(
+                    "{:<8} {:<6} {:<11} {:>10.1} {:>8.2}x",
+                    name,
+                    algo.to_string(),
+                    kind,
+                    mb(time),
+                    speedup
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,294 @@
+//! `lr_grammar!` builds the parsing table of an inline grammar at compile
+//! time and expands to the parser module of `lr0_gen::emit_rust`.
+//!
+//! ```
+//! use lr0_gen_macros::lr_grammar;
+//!
+//! lr_grammar! {
+//!     mod expr (lalr1);
+//!     S -> E;
+//!     E -> E + T | T;
+//!     T -> T * F | F;
+//!     F -> a | ( E );
+//! }
+//!
+//! struct Depth;
+//!
+//! impl expr::Semantics for Depth {
+//!     type Value = usize;
+//!     fn on_shift(&mut self, _: expr::Token, _: usize) -> usize {
+//!         0
+//!     }
+//!     fn on_reduce(&mut self, _: usize, _: char, values: Vec<usize>) -> usize {
+//!         values.into_iter().max().unwrap_or(0) + 1
+//!     }
+//! }
+//!
+//! assert_eq!(expr::parse("a+a*a", Depth), Ok(5));
+//! assert!(expr::parse("a+", Depth).is_err());
+//! ```
+//!
+//! Conflicts are compile errors pointing at the rules that can be reduced:
+//!
+//! ```compile_fail
+//! lr0_gen_macros::lr_grammar! {
+//!     mod assign (slr1);
+//!     S -> A;
+//!     A -> L = R | R;
+//!     L -> * R | a;
+//!     R -> L;
+//! }
+//! ```
+
+use std::{collections::HashSet, iter::Peekable};
+
+use lr0_gen::{conflicts, emit_rust, Action, Algo, Grammar, ERROR};
+use proc_macro::{
+    token_stream, Delimiter, Group, Ident, Literal, Punct, Spacing, Span, TokenStream, TokenTree,
+};
+
+/// Parser module from inline grammar. First line is `mod name;` with
+/// optional visibility and algorithm (`mod name (lr1);`, LALR(1) by
+/// default), then rules `A -> x y z | w;` where the first rule is the
+/// starting one. Left sides are non-terminals, every other symbol is
+/// a terminal. Symbols are characters: identifiers are split into them,
+/// punctuation, char and string literals and brackets are used as they
+/// are, `eps` is the empty string and `error` the recovery terminal.
+#[proc_macro]
+pub fn lr_grammar(input: TokenStream) -> TokenStream {
+    match expand(input) {
+        Ok(x) => x,
+        Err(errors) => errors
+            .into_iter()
+            .map(|(span, message)| compile_error(span, &message))
+            .collect(),
+    }
+}
+
+type Error = (Span, String);
+type Tokens = Peekable<token_stream::IntoIter>;
+
+struct Rule {
+    left: char,
+    right: Vec<char>,
+    // first symbol of the alternative
+    span: Span,
+}
+
+fn expand(input: TokenStream) -> Result<TokenStream, Vec<Error>> {
+    let mut tokens = input.into_iter().peekable();
+    let (vis, name, algo) = header(&mut tokens).map_err(|e| vec![e])?;
+    let rules = rules(&mut tokens).map_err(|e| vec![e])?;
+    if rules.is_empty() {
+        return Err(vec![(
+            name.span(),
+            "grammar must contain atleast one rule".to_string(),
+        )]);
+    }
+
+    let non_terms: HashSet<char> = rules.iter().map(|x| x.left).collect();
+    let terms: HashSet<char> = rules
+        .iter()
+        .flat_map(|x| x.right.iter().copied())
+        .filter(|x| !non_terms.contains(x))
+        .collect();
+    let mut g = Grammar::new(non_terms, terms);
+    for r in &rules {
+        let right: String = r.right.iter().collect();
+        g.add_rule(r.left, &right)
+            .map_err(|e| vec![(r.span, e.to_string())])?;
+    }
+    g.update_sets();
+
+    let table = algo.build(&g);
+    let mut errors = vec![];
+    for c in conflicts(table.as_ref()) {
+        for a in &c.actions {
+            let rule = match a {
+                Action::Reduction(r) => *r,
+                Action::Accept => 0,
+                _ => continue,
+            };
+            let message = format!(
+                "{} table conflict: {}, R{} is {}",
+                algo,
+                c,
+                rule,
+                g.rules()[rule]
+            );
+            errors.push((rules[rule].span, message));
+        }
+    }
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+
+    let body: TokenStream = emit_rust(table.as_ref())
+        .parse()
+        .map_err(|e| vec![(Span::call_site(), format!("generated parser : {}", e))])?;
+    let mut res: Vec<TokenTree> = vis;
+    res.push(Ident::new("mod", name.span()).into());
+    res.push(name.into());
+    res.push(Group::new(Delimiter::Brace, body).into());
+    Ok(res.into_iter().collect())
+}
+
+// `pub mod name (algo);`
+fn header(tokens: &mut Tokens) -> Result<(Vec<TokenTree>, Ident, Algo), Error> {
+    let mut vis = vec![];
+    if let Some(TokenTree::Ident(x)) = tokens.peek() {
+        if x.to_string() == "pub" {
+            vis.push(tokens.next().unwrap());
+            if let Some(TokenTree::Group(g)) = tokens.peek() {
+                if g.delimiter() == Delimiter::Parenthesis {
+                    vis.push(tokens.next().unwrap());
+                }
+            }
+        }
+    }
+    let expected = "expected `mod name;` before rules";
+    match tokens.next() {
+        Some(TokenTree::Ident(x)) if x.to_string() == "mod" => (),
+        Some(x) => return Err((x.span(), expected.to_string())),
+        None => return Err((Span::call_site(), expected.to_string())),
+    }
+    let name = match tokens.next() {
+        Some(TokenTree::Ident(x)) => x,
+        Some(x) => return Err((x.span(), expected.to_string())),
+        None => return Err((Span::call_site(), expected.to_string())),
+    };
+    let mut algo = Algo::LALR1;
+    if let Some(TokenTree::Group(g)) = tokens.peek() {
+        if g.delimiter() == Delimiter::Parenthesis {
+            algo = g
+                .stream()
+                .to_string()
+                .trim()
+                .parse()
+                .map_err(|e| (g.span(), e))?;
+            tokens.next();
+        }
+    }
+    match tokens.next() {
+        Some(TokenTree::Punct(x)) if x.as_char() == ';' => Ok((vis, name, algo)),
+        Some(x) => Err((x.span(), "expected `;` after module name".to_string())),
+        None => Err((name.span(), "expected `;` after module name".to_string())),
+    }
+}
+
+// `A -> x y | z;` until the end of input, one rule per alternative
+fn rules(tokens: &mut Tokens) -> Result<Vec<Rule>, Error> {
+    let mut res = vec![];
+    while let Some(tt) = tokens.next() {
+        let left = match &tt {
+            TokenTree::Ident(x) if x.to_string().chars().count() == 1 => {
+                x.to_string().chars().next().unwrap()
+            }
+            x => {
+                let message = "left side must be one character non-terminal";
+                return Err((x.span(), message.to_string()));
+            }
+        };
+        for c in ['-', '>'] {
+            match tokens.next() {
+                Some(TokenTree::Punct(x)) if x.as_char() == c => (),
+                Some(x) => return Err((x.span(), "expected `->`".to_string())),
+                None => return Err((tt.span(), "expected `->`".to_string())),
+            }
+        }
+
+        let mut right = vec![];
+        let mut span = None;
+        loop {
+            match tokens.next() {
+                Some(TokenTree::Punct(x)) if x.as_char() == '|' || x.as_char() == ';' => {
+                    res.push(Rule {
+                        left,
+                        right: std::mem::take(&mut right),
+                        span: span.take().unwrap_or(x.span()),
+                    });
+                    if x.as_char() == ';' {
+                        break;
+                    }
+                }
+                Some(x) => {
+                    span.get_or_insert(x.span());
+                    symbols(&x, &mut right)?;
+                }
+                None => return Err((tt.span(), "missing `;` after rule".to_string())),
+            }
+        }
+    }
+    Ok(res)
+}
+
+fn symbols(tt: &TokenTree, out: &mut Vec<char>) -> Result<(), Error> {
+    match tt {
+        TokenTree::Ident(x) => match x.to_string().as_str() {
+            "eps" => (),
+            "error" => out.push(ERROR),
+            x => out.extend(x.chars()),
+        },
+        TokenTree::Punct(x) => out.push(x.as_char()),
+        TokenTree::Literal(x) => out.extend(literal(x)?),
+        TokenTree::Group(x) => {
+            let (open, close) = match x.delimiter() {
+                Delimiter::Parenthesis => (Some('('), Some(')')),
+                Delimiter::Bracket => (Some('['), Some(']')),
+                Delimiter::Brace => (Some('{'), Some('}')),
+                Delimiter::None => (None, None),
+            };
+            out.extend(open);
+            for inner in x.stream() {
+                symbols(&inner, out)?;
+            }
+            out.extend(close);
+        }
+    }
+    Ok(())
+}
+
+// characters of char, string or number literal
+fn literal(lit: &Literal) -> Result<Vec<char>, Error> {
+    let text = lit.to_string();
+    let inner = match text.chars().next() {
+        Some('\'') | Some('"') => &text[1..text.len() - 1],
+        _ => return Ok(text.chars().collect()),
+    };
+    let mut res = vec![];
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            res.push(c);
+            continue;
+        }
+        res.push(match chars.next() {
+            Some('n') => '\n',
+            Some('t') => '\t',
+            Some('r') => '\r',
+            Some('0') => '\0',
+            Some(x @ ('\\' | '\'' | '"')) => x,
+            _ => return Err((lit.span(), "unsupported escape in literal".to_string())),
+        });
+    }
+    Ok(res)
+}
+
+fn compile_error(span: Span, message: &str) -> TokenStream {
+    let mut lit = Literal::string(message);
+    lit.set_span(span);
+    let mut args = Group::new(Delimiter::Parenthesis, TokenTree::Literal(lit).into());
+    args.set_span(span);
+    let mut bang = Punct::new('!', Spacing::Alone);
+    bang.set_span(span);
+    let mut semi = Punct::new(';', Spacing::Alone);
+    semi.set_span(span);
+    [
+        TokenTree::Ident(Ident::new("compile_error", span)),
+        bang.into(),
+        args.into(),
+        semi.into(),
+    ]
+    .into_iter()
+    .collect()
+}
@@ -0,0 +1,101 @@
+use lr0_gen_macros::lr_grammar;
+
+lr_grammar! {
+    mod expr;
+    S -> E;
+    E -> E + T | T;
+    T -> T * F | F;
+    F -> a | ( E );
+}
+
+lr_grammar! {
+    pub(crate) mod simple (lr1);
+    S -> A;
+    A -> aAb | eps;
+}
+
+lr_grammar! {
+    mod recovery (slr1);
+    S -> L;
+    L -> L T | T;
+    T -> a '=' a ';' | error ';';
+}
+
+// Evaluator of expressions where `a` is variable
+struct Calc(i64);
+
+impl expr::Semantics for Calc {
+    type Value = i64;
+
+    fn on_shift(&mut self, token: expr::Token, _: usize) -> i64 {
+        match token {
+            expr::Token::A => self.0,
+            _ => 0,
+        }
+    }
+
+    fn on_reduce(&mut self, rule: usize, _: char, values: Vec<i64>) -> i64 {
+        match rule {
+            1 => values[0] + values[2],
+            3 => values[0] * values[2],
+            6 => values[1],
+            _ => values[0],
+        }
+    }
+}
+
+// Rules in order of reduction
+struct Rules;
+
+impl simple::Semantics for Rules {
+    type Value = Vec<usize>;
+
+    fn on_shift(&mut self, _: simple::Token, _: usize) -> Vec<usize> {
+        vec![]
+    }
+
+    fn on_reduce(&mut self, rule: usize, _: char, values: Vec<Vec<usize>>) -> Vec<usize> {
+        let mut res: Vec<usize> = values.into_iter().flatten().collect();
+        res.push(rule);
+        res
+    }
+}
+
+impl recovery::Semantics for Rules {
+    type Value = Vec<usize>;
+
+    fn on_shift(&mut self, _: recovery::Token, _: usize) -> Vec<usize> {
+        vec![]
+    }
+
+    fn on_reduce(&mut self, rule: usize, _: char, values: Vec<Vec<usize>>) -> Vec<usize> {
+        let mut res: Vec<usize> = values.into_iter().flatten().collect();
+        res.push(rule);
+        res
+    }
+}
+
+#[test]
+fn expression() {
+    assert_eq!(expr::parse("a+a*(a+a)", Calc(3)), Ok(21));
+    let e = expr::parse("a+*a", Calc(3)).unwrap_err();
+    assert_eq!((e.offset, e.found), (2, Some('*')));
+    assert_eq!(
+        e.to_string(),
+        "unexpected '*' at 2 in state 4, expected '(', 'a'"
+    );
+}
+
+#[test]
+fn epsilon() {
+    assert_eq!(simple::parse("", Rules), Ok(vec![2, 0]));
+    assert_eq!(simple::parse("aabb", Rules), Ok(vec![2, 1, 1, 0]));
+    assert!(simple::parse("aab", Rules).is_err());
+}
+
+#[test]
+fn literals() {
+    assert_eq!(recovery::Token::from_char(';'), Some(recovery::Token::Semi));
+    assert_eq!(recovery::Token::Error.to_string(), "error");
+    assert_eq!(recovery::parse("a=a;a=a;", Rules), Ok(vec![3, 2, 3, 1, 0]));
+}
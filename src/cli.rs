@@ -0,0 +1,915 @@
+use std::{
+    fs,
+    io::{self, BufRead},
+    path::Path,
+    process::ExitCode,
+};
+
+use lr0_gen::{
+    bison_report, compare, conflicts, emit_c, emit_js, emit_rust, html_report, json, json_report,
+    Algo, CompressedTable, Csv, Grammar, Html, Latex, Markdown, Renderer, StackAutomata,
+    StoredTable, Sym, Table, Text,
+};
+
+use crate::repl::Repl;
+
+// Exit codes of the binary
+pub(crate) const ACCEPTED: u8 = 0;
+pub(crate) const REJECTED: u8 = 1;
+pub(crate) const GRAMMAR_ERROR: u8 = 2;
+pub(crate) const CONFLICTS: u8 = 3;
+pub(crate) const USAGE: u8 = 64;
+
+const HELP: &str = "usage: lr0_gen FILE
+       lr0_gen COMMAND [OPTIONS] FILE
+
+FILE is a grammar or a table saved by table --save (then --algo is ignored)
+
+commands:
+  table         print parsing table, exits with 3 on conflicts
+  parse         parse inputs (stdin lines when none given), exits with 1 on rejection
+  states        print states of automaton with items
+  first-follow  print nullable, FIRST and FOLLOW of non terminals
+  check         report conflicts (exit 3) and inputs where table disagrees with grammar (exit 1)
+  interactive   prompt for table type and inputs (same as no command)
+  repl          explore grammar with commands, see help inside
+  generate      standalone parser of the table in Rust, C or JS, exits with 3 on conflicts
+  report        offline HTML page with rules, FIRST/FOLLOW, states, table and conflicts
+                (JSON for --format json, Bison .output layout for --format bison),
+                exits with 3 on conflicts
+
+options:
+  --algo lr0|slr1|lalr1|lr1   table construction (default lalr1)
+  --input TEXT                input string, can be repeated
+  --input-file PATH           file with one input per line, can be repeated
+  --format FORMAT             output format (default text), json and csv for parse,
+                              json for first-follow, check and report, table renders as csv,
+                              markdown, html (conflicts highlighted) or latex, report as
+                              json or bison
+  --trace                     print steps of parser for text format
+  --save PATH                 table writes the table to PATH (binary for .bin, JSON otherwise)
+  --compress                  table prints the compressed table and its size against the original
+  --classic                   table prints ACTION with targets of shifts (s5, r3) and GOTO over non terminals
+  --lang rust|c|js            language of generate (default rust)
+  --output PATH               generate and report write to PATH instead of stdout, C needs it
+                              and writes PATH.h and PATH.c prefixed by name of PATH,
+                              JS writes typings next to the module (x.js and x.d.ts)
+  -h, --help                  print this help
+
+exit codes: 0 accepted, 1 rejected, 2 grammar error, 3 conflicts, 64 usage";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Command {
+    Table,
+    Parse,
+    States,
+    FirstFollow,
+    Check,
+    Interactive,
+    Repl,
+    Generate,
+    Report,
+    Help,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Lang {
+    Rust,
+    C,
+    Js,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Format {
+    Text,
+    Json,
+    Csv,
+    Markdown,
+    Html,
+    Latex,
+    Bison,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct Args {
+    pub(crate) command: Command,
+    pub(crate) file: String,
+    // None lets interactive mode ask for it
+    pub(crate) algo: Option<Algo>,
+    pub(crate) inputs: Vec<String>,
+    pub(crate) input_files: Vec<String>,
+    pub(crate) format: Format,
+    pub(crate) trace: bool,
+    pub(crate) save: Option<String>,
+    pub(crate) compress: bool,
+    pub(crate) classic: bool,
+    pub(crate) lang: Lang,
+    pub(crate) output: Option<String>,
+}
+
+impl Args {
+    pub(crate) fn parse(args: &[String]) -> Result<Self, String> {
+        let mut res = Self {
+            command: Command::Interactive,
+            file: String::new(),
+            algo: None,
+            inputs: vec![],
+            input_files: vec![],
+            format: Format::Text,
+            trace: false,
+            save: None,
+            compress: false,
+            classic: false,
+            lang: Lang::Rust,
+            output: None,
+        };
+        let mut positional: Vec<&str> = vec![];
+        let mut iter = args.iter();
+        while let Some(arg) = iter.next() {
+            let mut value = |name: &str| match iter.next() {
+                Some(x) => Ok(x.clone()),
+                None => Err(format!("missing value of {}", name)),
+            };
+            match arg.as_str() {
+                "-h" | "--help" => res.command = Command::Help,
+                "--algo" => res.algo = Some(value("--algo")?.parse()?),
+                "--input" => res.inputs.push(value("--input")?),
+                "--input-file" => res.input_files.push(value("--input-file")?),
+                "--format" => {
+                    res.format = match value("--format")?.as_str() {
+                        "text" => Format::Text,
+                        "json" => Format::Json,
+                        "csv" => Format::Csv,
+                        "markdown" => Format::Markdown,
+                        "html" => Format::Html,
+                        "latex" => Format::Latex,
+                        "bison" => Format::Bison,
+                        x => {
+                            let names = "text, json, csv, markdown, html, latex or bison";
+                            return Err(format!("unknown format {}, use {}", x, names));
+                        }
+                    }
+                }
+                "--trace" => res.trace = true,
+                "--save" => res.save = Some(value("--save")?),
+                "--compress" => res.compress = true,
+                "--classic" => res.classic = true,
+                "--lang" => {
+                    res.lang = match value("--lang")?.as_str() {
+                        "rust" => Lang::Rust,
+                        "c" => Lang::C,
+                        "js" => Lang::Js,
+                        x => return Err(format!("unknown language {}, use rust, c or js", x)),
+                    }
+                }
+                "--output" => res.output = Some(value("--output")?),
+                x if x.starts_with("--") => return Err(format!("unknown option {}", x)),
+                x => positional.push(x),
+            }
+        }
+        if res.command == Command::Help {
+            return Ok(res);
+        }
+
+        let file = match positional.as_slice() {
+            [file] => file,
+            [command, file] => {
+                res.command = match *command {
+                    "table" => Command::Table,
+                    "parse" => Command::Parse,
+                    "states" => Command::States,
+                    "first-follow" => Command::FirstFollow,
+                    "check" => Command::Check,
+                    "interactive" => Command::Interactive,
+                    "repl" => Command::Repl,
+                    "generate" => Command::Generate,
+                    "report" => Command::Report,
+                    x => return Err(format!("unknown command {}", x)),
+                };
+                file
+            }
+            [] => return Err("no grammar file given".to_string()),
+            _ => return Err("too many arguments".to_string()),
+        };
+        res.file = file.to_string();
+
+        let formats: &[Format] = match res.command {
+            Command::Parse => &[Format::Text, Format::Json, Format::Csv],
+            Command::Table => &[
+                Format::Text,
+                Format::Csv,
+                Format::Markdown,
+                Format::Html,
+                Format::Latex,
+            ],
+            Command::FirstFollow | Command::Check => &[Format::Text, Format::Json],
+            Command::Report => &[Format::Text, Format::Json, Format::Bison],
+            _ => &[Format::Text],
+        };
+        if !formats.contains(&res.format) {
+            return Err(format!("format is not supported by {}", positional[0]));
+        }
+        let table_only = res.save.is_some() || res.compress || res.classic;
+        if table_only && res.command != Command::Table {
+            return Err("--save, --compress and --classic are supported only by table".to_string());
+        }
+        if res.compress && res.classic {
+            return Err("--compress and --classic can not be combined".to_string());
+        }
+        if res.command != Command::Generate && res.lang != Lang::Rust {
+            return Err("--lang is supported only by generate".to_string());
+        }
+        if !matches!(res.command, Command::Generate | Command::Report) && res.output.is_some() {
+            return Err("--output is supported only by generate and report".to_string());
+        }
+        if res.lang == Lang::C && res.output.is_none() {
+            return Err("C parser needs --output".to_string());
+        }
+        Ok(res)
+    }
+
+    // inputs from options, stdin lines when there are none
+    fn read_inputs(&self, stdin: bool) -> Result<Vec<String>, String> {
+        let mut res = self.inputs.clone();
+        for path in &self.input_files {
+            let text =
+                fs::read_to_string(path).map_err(|e| format!("cannot read {} : {}", path, e))?;
+            res.extend(text.lines().map(|x| x.trim_end_matches('\r').to_string()));
+        }
+        if res.is_empty() && self.input_files.is_empty() && stdin {
+            for line in io::stdin().lock().lines() {
+                res.push(line.map_err(|e| format!("cannot read input : {}", e))?);
+            }
+        }
+        Ok(res)
+    }
+}
+
+pub(crate) fn run(args: &[String]) -> ExitCode {
+    let args = match Args::parse(args) {
+        Ok(x) => x,
+        Err(e) => {
+            eprintln!("error: {}\n\n{}", e, HELP);
+            return ExitCode::from(USAGE);
+        }
+    };
+    if args.command == Command::Help {
+        println!("{}", HELP);
+        return ExitCode::SUCCESS;
+    }
+
+    let (g, stored) = match load(&args.file) {
+        Ok(x) => x,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            return ExitCode::from(GRAMMAR_ERROR);
+        }
+    };
+
+    if args.command == Command::Interactive {
+        return match interactive(&g, args.algo) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(e) => {
+                eprintln!("error: {}", e);
+                ExitCode::from(USAGE)
+            }
+        };
+    }
+
+    if args.command == Command::Repl {
+        let mut repl = Repl::new(g, args.algo.unwrap_or(Algo::LALR1));
+        return match repl.run() {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(e) => {
+                eprintln!("error: {}", e);
+                ExitCode::from(USAGE)
+            }
+        };
+    }
+
+    let inputs = match args.read_inputs(args.command == Command::Parse) {
+        Ok(x) => x,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            return ExitCode::from(USAGE);
+        }
+    };
+    let (out, code) = execute(&args, &g, stored.as_ref().map(|x| x as &dyn Table), &inputs);
+    print!("{}", out);
+    ExitCode::from(code)
+}
+
+// grammar from grammar file or from saved table
+fn load(path: &str) -> Result<(Grammar, Option<StoredTable>), String> {
+    let bytes = fs::read(path).map_err(|e| format!("cannot read {} : {}", path, e))?;
+    if StoredTable::is_stored(&bytes) {
+        let table = StoredTable::load(&bytes)?;
+        return Ok((table.grammar().clone(), Some(table)));
+    }
+    let text = String::from_utf8(bytes).map_err(|_| format!("{} is not UTF-8", path))?;
+    Ok((Grammar::from_text(&text)?, None))
+}
+
+// output and exit code of non interactive command,
+// stored table is used instead of building one
+pub(crate) fn execute(
+    args: &Args,
+    g: &Grammar,
+    stored: Option<&dyn Table>,
+    inputs: &[String],
+) -> (String, u8) {
+    // automaton of stored table is rebuilt by the algorithm it was made with
+    let algo = match stored {
+        Some(t) => match Algo::of(t) {
+            Some(x) => x,
+            None if matches!(args.command, Command::States | Command::Report) => {
+                return (format!("error: unknown table {}\n", t.name()), USAGE)
+            }
+            None => args.algo.unwrap_or(Algo::LALR1),
+        },
+        None => args.algo.unwrap_or(Algo::LALR1),
+    };
+    let built;
+    let table = match stored {
+        Some(t) => t,
+        None => {
+            built = algo.build(g);
+            built.as_ref()
+        }
+    };
+    match args.command {
+        Command::Table => self::table(table, args),
+        Command::Parse => parse(table, inputs, args.format, args.trace),
+        Command::States => (algo.states(g).join("\n"), ACCEPTED),
+        Command::FirstFollow => (first_follow(g, args.format), ACCEPTED),
+        Command::Check => {
+            let name = stored.map_or(algo.to_string(), |t| t.name().to_string());
+            check(table, &name, inputs, args.format)
+        }
+        Command::Generate => generate(table, args.lang, args.output.as_deref()),
+        Command::Report => report(g, algo, table, args.format, args.output.as_deref()),
+        Command::Interactive | Command::Repl | Command::Help => unreachable!(),
+    }
+}
+
+fn table(table: &dyn Table, args: &Args) -> (String, u8) {
+    let compressed = args.compress.then(|| CompressedTable::new(table));
+    let shown: &dyn Table = match &compressed {
+        Some(x) => x,
+        None => table,
+    };
+    let renderer: &dyn Renderer = match args.format {
+        Format::Csv => &Csv,
+        Format::Markdown => &Markdown,
+        Format::Html => &Html,
+        Format::Latex => &Latex,
+        Format::Text | Format::Json | Format::Bison => &Text,
+    };
+    let mut out = match args.classic {
+        true => renderer.render_classic(table),
+        false => renderer.render(shown),
+    };
+    let found = conflicts(table);
+    // other formats are only the table so they can be used as they are
+    if args.format == Format::Text {
+        for c in &found {
+            out.push_str(&format!("conflict : {}\n", c));
+        }
+        if let Some(x) = &compressed {
+            out.push_str(&format!("compression : {}\n", x.stats()));
+        }
+    }
+    if let Some(path) = &args.save {
+        let stored = StoredTable::new(table);
+        let bytes = match path.ends_with(".bin") {
+            true => stored.to_bytes(),
+            false => stored.to_json().into_bytes(),
+        };
+        if let Err(e) = fs::write(path, bytes) {
+            out.push_str(&format!("error: cannot write {} : {}\n", path, e));
+            return (out, USAGE);
+        }
+    }
+    match found.is_empty() {
+        true => (out, ACCEPTED),
+        false => (out, CONFLICTS),
+    }
+}
+
+fn generate(table: &dyn Table, lang: Lang, output: Option<&str>) -> (String, u8) {
+    let code = match conflicts(table).is_empty() {
+        true => ACCEPTED,
+        false => CONFLICTS,
+    };
+    let files = match (lang, output) {
+        (Lang::Rust, None) => return (emit_rust(table), code),
+        (Lang::Rust, Some(path)) => vec![(path.to_string(), emit_rust(table))],
+        (Lang::C, path) => {
+            let path = path.unwrap_or_default();
+            let path = path.strip_suffix(".c").unwrap_or(path);
+            let name = Path::new(path)
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy();
+            let prefix: String = name
+                .chars()
+                .map(|x| if x.is_ascii_alphanumeric() { x } else { '_' })
+                .collect();
+            let (h, c) = emit_c(table, &prefix);
+            vec![(format!("{}.h", path), h), (format!("{}.c", path), c)]
+        }
+        (Lang::Js, None) => return (emit_js(table).0, code),
+        (Lang::Js, Some(path)) => {
+            let typings = match path.rsplit_once('.') {
+                Some((stem, "mjs")) => format!("{}.d.mts", stem),
+                Some((stem, "js")) => format!("{}.d.ts", stem),
+                _ => format!("{}.d.ts", path),
+            };
+            let (js, ts) = emit_js(table);
+            vec![(path.to_string(), js), (typings, ts)]
+        }
+    };
+    for (path, text) in files {
+        if let Err(e) = fs::write(&path, text) {
+            return (format!("error: cannot write {} : {}\n", path, e), USAGE);
+        }
+    }
+    (String::new(), code)
+}
+
+fn report(
+    g: &Grammar,
+    algo: Algo,
+    table: &dyn Table,
+    format: Format,
+    output: Option<&str>,
+) -> (String, u8) {
+    let code = match conflicts(table).is_empty() {
+        true => ACCEPTED,
+        false => CONFLICTS,
+    };
+    let page = match format {
+        Format::Json => json_report(g, algo),
+        Format::Bison => bison_report(g, algo),
+        _ => html_report(g, algo),
+    };
+    match output {
+        None => (page, code),
+        Some(path) => match fs::write(path, page) {
+            Ok(()) => (String::new(), code),
+            Err(e) => (format!("error: cannot write {} : {}\n", path, e), USAGE),
+        },
+    }
+}
+
+fn parse(table: &dyn Table, inputs: &[String], format: Format, trace: bool) -> (String, u8) {
+    let mut code = ACCEPTED;
+    let mut out = String::new();
+    let mut objects = vec![];
+    for input in inputs {
+        let mut autom = StackAutomata::new(table, input);
+        autom.enable_trace();
+        let (tree, errors) = autom.run_recovering();
+        let mut messages: Vec<String> = errors.iter().map(|x| x.to_string()).collect();
+        if tree.is_some() && errors.is_empty() && !autom.consumed() {
+            messages.push("error: input continues after accept".to_string());
+        }
+        let accepted = tree.is_some() && messages.is_empty();
+        if !accepted {
+            code = REJECTED;
+        }
+        let steps = autom.trace().unwrap();
+        match format {
+            Format::Text => {
+                if trace {
+                    out.push_str(&format!("{}\n", steps));
+                }
+                match accepted {
+                    true => out.push_str(&format!("accepted : {}\n", input)),
+                    false => out.push_str(&format!("rejected : {}\n", input)),
+                }
+                for m in &messages {
+                    out.push_str(&format!("{}\n", m));
+                }
+                if !accepted {
+                    let mut autom = StackAutomata::new(table, input);
+                    let (res, repairs) = autom.run_repairing(3);
+                    for r in repairs {
+                        out.push_str(&format!("repair : {}\n", r));
+                    }
+                    if let Ok(tree) = res {
+                        out.push_str(&format!("repaired : {}\n", tree.text()));
+                    }
+                }
+                if let Some(d) = compare(table, input) {
+                    out.push_str(&format!("earley disagrees : {}\n", d));
+                }
+                if let (true, Some(tree)) = (accepted, &tree) {
+                    out.push_str(&tree.indented());
+                }
+                out.push('\n');
+            }
+            Format::Csv => out.push_str(&format!("{}\n", steps.to_csv())),
+            Format::Json => objects.push(json::object([
+                ("input", json::string(input)),
+                ("accepted", accepted.to_string()),
+                (
+                    "errors",
+                    json::array(messages.iter().map(|x| json::string(x))),
+                ),
+                (
+                    "tree",
+                    match (accepted, &tree) {
+                        (true, Some(tree)) => json::string(&tree.sexp()),
+                        _ => "null".to_string(),
+                    },
+                ),
+                ("trace", steps.to_json()),
+            ])),
+            // rejected by Args::parse
+            Format::Markdown | Format::Html | Format::Latex | Format::Bison => unreachable!(),
+        }
+    }
+    if format == Format::Json {
+        out = json::array(objects) + "\n";
+    }
+    (out, code)
+}
+
+fn syms(set: impl IntoIterator<Item = Sym>) -> Vec<String> {
+    let mut res: Vec<Sym> = set.into_iter().collect();
+    res.sort_by_key(|x| match x {
+        Sym::Normal(c) => (0, *c),
+        Sym::Eps => (1, ' '),
+    });
+    res.iter().map(|x| x.to_string()).collect()
+}
+
+pub(crate) fn first_follow(g: &Grammar, format: Format) -> String {
+    let mut non_terms: Vec<char> = g.non_terms().iter().copied().collect();
+    non_terms.sort();
+    let rows: Vec<(char, bool, Vec<String>, Vec<String>)> = non_terms
+        .iter()
+        .map(|n| {
+            let mut first = g.first_from(&[*n]);
+            let nullable = first.remove(&Sym::Eps);
+            let follow = g.follow(*n).iter().copied();
+            (*n, nullable, syms(first), syms(follow))
+        })
+        .collect();
+    match format {
+        Format::Json => {
+            json::array(rows.iter().map(|(n, nullable, first, follow)| {
+                json::object([
+                    ("symbol", json::string(&n.to_string())),
+                    ("nullable", nullable.to_string()),
+                    ("first", json::array(first.iter().map(|x| json::string(x)))),
+                    (
+                        "follow",
+                        json::array(follow.iter().map(|x| json::string(x))),
+                    ),
+                ])
+            })) + "\n"
+        }
+        _ => {
+            let mut out = String::new();
+            for (n, nullable, first, follow) in &rows {
+                out.push_str(&format!("{}\n", n));
+                out.push_str(&format!("  nullable : {}\n", nullable));
+                out.push_str(&format!("  first    : {}\n", first.join(" ")));
+                out.push_str(&format!("  follow   : {}\n", follow.join(" ")));
+            }
+            out
+        }
+    }
+}
+
+fn check(table: &dyn Table, name: &str, inputs: &[String], format: Format) -> (String, u8) {
+    let found = conflicts(table);
+    let disagreements: Vec<(&String, String)> = inputs
+        .iter()
+        .filter_map(|x| compare(table, x).map(|d| (x, d.to_string())))
+        .collect();
+    let code = match (found.is_empty(), disagreements.is_empty()) {
+        (false, _) => CONFLICTS,
+        (true, false) => REJECTED,
+        (true, true) => ACCEPTED,
+    };
+
+    let out = match format {
+        Format::Json => {
+            json::object([
+                (
+                    "conflicts",
+                    json::array(found.iter().map(|c| {
+                        json::object([
+                            ("state", c.state.to_string()),
+                            (
+                                "symbols",
+                                json::array(c.syms.iter().map(|x| json::string(&x.to_string()))),
+                            ),
+                            (
+                                "actions",
+                                json::array(c.actions.iter().map(|x| json::string(&x.to_string()))),
+                            ),
+                        ])
+                    })),
+                ),
+                (
+                    "disagreements",
+                    json::array(disagreements.iter().map(|(input, d)| {
+                        json::object([("input", json::string(input)), ("message", json::string(d))])
+                    })),
+                ),
+            ]) + "\n"
+        }
+        _ => {
+            let mut out = String::new();
+            for c in &found {
+                out.push_str(&format!("conflict : {}\n", c));
+            }
+            for (input, d) in &disagreements {
+                out.push_str(&format!("{} : {}\n", input, d));
+            }
+            if code == ACCEPTED {
+                out.push_str(&format!("{} table has no conflicts\n", name));
+            }
+            out
+        }
+    };
+    (out, code)
+}
+
+fn get_input<F>(pred: F) -> Option<String>
+where
+    F: Fn(&String) -> bool,
+{
+    let stdin = io::stdin();
+    loop {
+        let mut buf = String::new();
+        match stdin.read_line(&mut buf) {
+            Ok(0) | Err(_) => return None,
+            Ok(_) => (),
+        }
+        let buf = buf.trim_end_matches(['\n', '\r']).to_string();
+        if pred(&buf) {
+            return Some(buf);
+        }
+    }
+}
+
+fn interactive(g: &Grammar, algo: Option<Algo>) -> Result<(), &'static str> {
+    let algo = match algo {
+        Some(x) => x,
+        None => {
+            println!("1. LR0\n2. SLR(1)\n3. LR(1)\n4. LALR(1)");
+            let ttype = get_input(|x: &String| matches!(x.as_str(), "1" | "2" | "3" | "4"));
+            match ttype.as_deref() {
+                Some("1") => Algo::LR0,
+                Some("2") => Algo::SLR1,
+                Some("3") => Algo::LR1,
+                Some("4") => Algo::LALR1,
+                _ => return Err("Bad input"),
+            }
+        }
+    };
+
+    let lrtab: Box<dyn Table> = algo.build(g);
+    println!("{}", lrtab);
+
+    loop {
+        println!("Write string :");
+
+        let input = match get_input(|_| true) {
+            Some(s) => s,
+            None => break,
+        };
+        if input == "quit" {
+            break;
+        }
+
+        // trace, errors and repairs are shown by the parse command
+        let mut autom = StackAutomata::new(lrtab.as_ref(), input.as_str());
+        let _ = autom.run();
+        println!("{}", autom);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(line: &str) -> Result<Args, String> {
+        let v: Vec<String> = line.split(' ').map(|x| x.to_string()).collect();
+        Args::parse(&v)
+    }
+
+    fn gramm(file: &str) -> Grammar {
+        Grammar::from_text(file).unwrap()
+    }
+
+    #[test]
+    fn arguments() {
+        let a = args("parse --algo lr1 --input a+a --input (a --format json g.gram").unwrap();
+        assert_eq!(a.command, Command::Parse);
+        assert_eq!(a.file, "g.gram");
+        assert_eq!(a.algo, Some(Algo::LR1));
+        assert_eq!(a.inputs, vec!["a+a", "(a"]);
+        assert_eq!(a.format, Format::Json);
+
+        let a = args("g.gram").unwrap();
+        assert_eq!(a.command, Command::Interactive);
+        assert_eq!(a.algo, None);
+
+        assert!(args("table --algo lr2 g.gram").is_err());
+        assert!(args("states --format csv g.gram").is_err());
+        assert!(args("build g.gram").is_err());
+        assert!(args("parse --input").is_err());
+        assert!(args("parse a b c").is_err());
+    }
+
+    #[test]
+    fn exit_codes() {
+        let g = gramm(include_str!("../examples/assign.gram"));
+        let lr0 = args("check --algo lr0 assign.gram").unwrap();
+        let (out, code) = execute(&lr0, &g, None, &[]);
+        assert_eq!(code, CONFLICTS);
+        assert!(out.starts_with("conflict : state"));
+        let lr1 = args("table --algo lr1 assign.gram").unwrap();
+        assert_eq!(execute(&lr1, &g, None, &[]).1, ACCEPTED);
+
+        let g = gramm(include_str!("../examples/example.gram"));
+        let parse = args("parse --algo slr1 example.gram").unwrap();
+        let inputs = ["a+a".to_string(), "a+".to_string()];
+        let (out, code) = execute(&parse, &g, None, &inputs[..1]);
+        assert_eq!(code, ACCEPTED);
+        assert!(out.starts_with("accepted : a+a\n"));
+        let (out, code) = execute(&parse, &g, None, &inputs);
+        assert_eq!(code, REJECTED);
+        assert!(out.contains("rejected : a+\nerror: unexpected end of input"));
+        assert!(out.contains("\nrepaired : a+a\n"));
+    }
+
+    #[test]
+    fn formats() {
+        let g = gramm(include_str!("../examples/simple.gram"));
+        let a = args("parse --format json simple.gram").unwrap();
+        let (out, _) = execute(&a, &g, None, &["ab".to_string()]);
+        assert!(out.starts_with("[{\"input\":\"ab\",\"accepted\":true,\"errors\":[],"));
+
+        let a = args("parse --format csv simple.gram").unwrap();
+        let (out, _) = execute(&a, &g, None, &["ab".to_string()]);
+        assert!(out.starts_with("step,states,symbols,input,action\n0,0,,ab$,shift"));
+
+        let a = args("first-follow simple.gram").unwrap();
+        let (out, _) = execute(&a, &g, None, &[]);
+        assert!(out.contains("A\n  nullable : true\n  first    : a\n  follow   : b eps\n"));
+    }
+
+    #[test]
+    fn saved_table() {
+        let g = gramm(include_str!("../examples/assign.gram"));
+        for name in ["lr0_gen_saved.json", "lr0_gen_saved.bin"] {
+            let path = std::env::temp_dir().join(name);
+            let path = path.to_str().unwrap();
+            let a = args(&format!("table --algo lr1 --save {} assign.gram", path)).unwrap();
+            assert_eq!(execute(&a, &g, None, &[]).1, ACCEPTED);
+
+            let (g, stored) = load(path).unwrap();
+            fs::remove_file(path).unwrap();
+            let table = stored.unwrap();
+            assert_eq!(table.name(), "LR1Table");
+            let a = args(&format!("parse {}", path)).unwrap();
+            let (out, code) = execute(&a, &g, Some(&table), &["a=a".to_string()]);
+            assert_eq!(code, ACCEPTED, "{}", out);
+        }
+        assert!(args("parse --save t.json g.gram").is_err());
+    }
+
+    #[test]
+    fn saved_table_algo() {
+        let g = gramm(include_str!("../examples/assign.gram"));
+        let table = Algo::SLR1.build(&g);
+        let stored = StoredTable::new(table.as_ref());
+        let (out, _) = execute(&args("states t.json").unwrap(), &g, Some(&stored), &[]);
+        assert_eq!(out, Algo::SLR1.states(&g).join("\n"));
+        let (out, code) = execute(
+            &args("report --format json t.json").unwrap(),
+            &g,
+            Some(&stored),
+            &[],
+        );
+        assert_eq!(code, CONFLICTS);
+        assert!(out.contains("\"algorithm\":\"slr1\""), "{}", out);
+
+        let compressed = CompressedTable::new(table.as_ref());
+        let stored = StoredTable::new(&compressed);
+        let (out, _) = execute(&args("states t.json").unwrap(), &g, Some(&stored), &[]);
+        assert_eq!(out, Algo::SLR1.states(&g).join("\n"));
+    }
+
+    #[test]
+    fn compressed_table() {
+        let g = gramm(include_str!("../examples/assign.gram"));
+        let (out, code) = execute(&args("table --compress g.gram").unwrap(), &g, None, &[]);
+        assert_eq!(code, ACCEPTED);
+        assert!(out.starts_with("LALR1Table\nstate\t|"));
+        assert!(out.lines().last().unwrap().starts_with("compression : "));
+        let a = args("table --compress --algo slr1 g.gram").unwrap();
+        let (out, code) = execute(&a, &g, None, &[]);
+        assert_eq!(code, CONFLICTS);
+        assert!(out.contains("conflict : state 6 on = : S/R5\ncompression : "));
+        assert!(args("parse --compress g.gram").is_err());
+    }
+
+    #[test]
+    fn classic_table() {
+        let g = gramm(include_str!("../examples/simple.gram"));
+        let a = args("table --classic --algo lr1 g.gram").unwrap();
+        let (out, code) = execute(&a, &g, None, &[]);
+        assert_eq!(code, ACCEPTED);
+        assert!(out.starts_with("LR1Table\nstate\t|a\tb\teps\t|A\tS\t\n0\t|s"));
+        assert!(args("table --classic --compress g.gram").is_err());
+        assert!(args("parse --classic g.gram").is_err());
+    }
+
+    #[test]
+    fn table_formats() {
+        let g = gramm(include_str!("../examples/assign.gram"));
+        let a = args("table --algo slr1 --format html g.gram").unwrap();
+        let (out, code) = execute(&a, &g, None, &[]);
+        assert_eq!(code, CONFLICTS);
+        assert!(out.starts_with("<table class=\"lr-table\">"));
+        assert!(out.contains("class=\"conflict\""));
+        assert!(!out.contains("conflict : "));
+        let a = args("table --format markdown --compress g.gram").unwrap();
+        assert!(execute(&a, &g, None, &[]).0.starts_with("**LALR1Table**"));
+        assert!(args("table --format json g.gram").is_err());
+        let a = args("table --format markdown --classic --algo slr1 g.gram").unwrap();
+        let (out, code) = execute(&a, &g, None, &[]);
+        assert_eq!(code, CONFLICTS);
+        assert!(out.contains("| 6 |  | **s7/r5** |"), "{}", out);
+        assert!(!out.contains("goto ="));
+        let a = args("table --format csv --classic g.gram").unwrap();
+        assert!(execute(&a, &g, None, &[])
+            .0
+            .starts_with("state,*,=,a,eps,goto A,goto L,"));
+        assert!(args("parse --format latex g.gram").is_err());
+    }
+
+    #[test]
+    fn report() {
+        let g = gramm(include_str!("../examples/assign.gram"));
+        let (out, code) = execute(&args("report --algo slr1 g.gram").unwrap(), &g, None, &[]);
+        assert_eq!(code, CONFLICTS);
+        assert!(out.starts_with("<!DOCTYPE html>"));
+
+        let path = std::env::temp_dir().join(format!("lr0_gen_report_{}.html", std::process::id()));
+        let path = path.to_str().unwrap();
+        let a = args(&format!("report --output {} g.gram", path)).unwrap();
+        assert_eq!(execute(&a, &g, None, &[]), (String::new(), ACCEPTED));
+        assert!(fs::read_to_string(path)
+            .unwrap()
+            .contains("<h1>LALR1Table report</h1>"));
+        fs::remove_file(path).unwrap();
+        let a = args("report --algo slr1 --format json g.gram").unwrap();
+        let (out, code) = execute(&a, &g, None, &[]);
+        assert_eq!(code, CONFLICTS);
+        let doc = json::parse(&out).unwrap();
+        assert_eq!(doc.get("algorithm").and_then(|x| x.as_str()), Some("slr1"));
+        let a = args("report --algo slr1 --format bison g.gram").unwrap();
+        let (out, code) = execute(&a, &g, None, &[]);
+        assert_eq!(code, CONFLICTS);
+        assert!(out.starts_with("State 6 conflicts: 1 shift/reduce\n"));
+        assert!(args("report --format csv g.gram").is_err());
+        assert!(args("table --format bison g.gram").is_err());
+        assert!(args("report --lang c --output x g.gram").is_err());
+        assert!(args("parse --output x g.gram").is_err());
+    }
+
+    #[test]
+    fn generate() {
+        let g = gramm(include_str!("../examples/assign.gram"));
+        let (out, code) = execute(&args("generate --algo lr1 g.gram").unwrap(), &g, None, &[]);
+        assert_eq!(code, ACCEPTED);
+        assert!(out.starts_with("// Parser generated by lr0_gen from LR1Table"));
+        let (_, code) = execute(&args("generate --algo lr0 g.gram").unwrap(), &g, None, &[]);
+        assert_eq!(code, CONFLICTS);
+
+        let path = std::env::temp_dir().join(format!("lr0_gen_cli_{}", std::process::id()));
+        let path = path.to_str().unwrap();
+        let a = args(&format!("generate --lang c --output {} g.gram", path)).unwrap();
+        assert_eq!(execute(&a, &g, None, &[]), (String::new(), ACCEPTED));
+        let header = fs::read_to_string(format!("{}.h", path)).unwrap();
+        let prefix = Path::new(path).file_name().unwrap().to_str().unwrap();
+        assert!(header.contains(&format!("int {}_push(", prefix)));
+        fs::remove_file(format!("{}.h", path)).unwrap();
+        fs::remove_file(format!("{}.c", path)).unwrap();
+
+        let (out, _) = execute(&args("generate --lang js g.gram").unwrap(), &g, None, &[]);
+        assert!(out.contains("export function parse(input, hooks = treeBuilder)"));
+        assert!(args("generate --lang c g.gram").is_err());
+        assert!(args("table --output t.rs g.gram").is_err());
+    }
+}
@@ -0,0 +1,164 @@
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    codegen::rust::emit_rust,
+    grammar::Grammar,
+    table::{
+        algo::{conflicts, Algo},
+        lrtable::Action,
+    },
+};
+
+/// Generates parser from `.gram` file in a build script, the module
+/// is written to `OUT_DIR` and cargo reruns the script when the grammar
+/// changes. Conflicts fail the build.
+///
+/// ```no_run
+/// // in fn main of build.rs
+/// lr0_gen::Build::new("src/expr.gram")
+///     .algo(lr0_gen::Algo::LR1)
+///     .compile();
+/// ```
+/// and in the crate
+/// ```ignore
+/// mod expr {
+///     include!(concat!(env!("OUT_DIR"), "/expr.rs"));
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Build {
+    grammar: PathBuf,
+    algo: Algo,
+    out_dir: Option<PathBuf>,
+    file_name: Option<String>,
+}
+
+impl Build {
+    pub fn new(grammar: impl AsRef<Path>) -> Self {
+        Self {
+            grammar: grammar.as_ref().to_path_buf(),
+            algo: Algo::LALR1,
+            out_dir: None,
+            file_name: None,
+        }
+    }
+
+    /// Table construction, LALR(1) by default
+    pub fn algo(mut self, algo: Algo) -> Self {
+        self.algo = algo;
+        self
+    }
+
+    /// Directory of the module instead of `OUT_DIR`
+    pub fn out_dir(mut self, dir: impl AsRef<Path>) -> Self {
+        self.out_dir = Some(dir.as_ref().to_path_buf());
+        self
+    }
+
+    /// Name of the module file, stem of the grammar with `.rs` by default
+    pub fn file_name(mut self, name: &str) -> Self {
+        self.file_name = Some(name.to_string());
+        self
+    }
+
+    /// Writes the module and gives its path, error is the
+    /// whole diagnostic for the user
+    pub fn run(&self) -> Result<PathBuf, String> {
+        println!("cargo:rerun-if-changed={}", self.grammar.display());
+        let path = self.grammar.display();
+        let text = fs::read_to_string(&self.grammar)
+            .map_err(|e| format!("cannot read {} : {}", path, e))?;
+        let g = Grammar::from_text(&text).map_err(|e| format!("{} : {}", path, e))?;
+        let table = self.algo.build(&g);
+
+        let found = conflicts(table.as_ref());
+        if !found.is_empty() {
+            let states = self.algo.states(&g);
+            let mut res = format!(
+                "{} : {} table has {} conflicts",
+                path,
+                self.algo,
+                found.len()
+            );
+            for c in &found {
+                res.push_str(&format!("\n\n{}\n", c));
+                for a in &c.actions {
+                    if let Action::Reduction(r) = a {
+                        res.push_str(&format!("  R{} is {}\n", r, g.rules()[*r]));
+                    }
+                }
+                for line in states[c.state].lines() {
+                    res.push_str(&format!("  {}\n", line));
+                }
+            }
+            return Err(res.trim_end().to_string());
+        }
+
+        let dir = match &self.out_dir {
+            Some(x) => x.clone(),
+            None => env::var_os("OUT_DIR")
+                .map(PathBuf::from)
+                .ok_or("OUT_DIR is not set, use out_dir outside of build script")?,
+        };
+        let name = match &self.file_name {
+            Some(x) => x.clone(),
+            None => {
+                let stem = self.grammar.file_stem().unwrap_or_default();
+                format!("{}.rs", stem.to_string_lossy())
+            }
+        };
+        let out = dir.join(name);
+        fs::write(&out, emit_rust(table.as_ref()))
+            .map_err(|e| format!("cannot write {} : {}", out.display(), e))?;
+        Ok(out)
+    }
+
+    /// Same as `run` but prints the error and fails the build script
+    pub fn compile(&self) -> PathBuf {
+        match self.run() {
+            Ok(x) => x,
+            Err(e) => {
+                eprintln!("error: {}", e);
+                std::process::exit(1);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn build() {
+        let dir = env::temp_dir().join(format!("lr0_gen_build_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let grammar = dir.join("assign.gram");
+        fs::write(&grammar, include_str!("../../examples/assign.gram")).unwrap();
+
+        let out = Build::new(&grammar).out_dir(&dir).run().unwrap();
+        assert_eq!(out, dir.join("assign.rs"));
+        assert!(fs::read_to_string(&out)
+            .unwrap()
+            .starts_with("// Parser generated by lr0_gen from LALR1Table"));
+
+        let err = Build::new(&grammar)
+            .algo(Algo::SLR1)
+            .out_dir(&dir)
+            .file_name("slr.rs")
+            .run()
+            .unwrap_err();
+        assert!(err.contains("assign.gram : slr1 table has 1 conflicts\n\nstate 6 on = : S/R5\n"));
+        assert!(err.contains("  R5 is R -> L\n  state 6 (on L)\n      A -> L . = R\n"));
+        assert!(!dir.join("slr.rs").exists());
+
+        assert!(Build::new(dir.join("missing.gram"))
+            .out_dir(&dir)
+            .run()
+            .is_err());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
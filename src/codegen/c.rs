@@ -0,0 +1,476 @@
+use crate::{
+    codegen::{token_name, Layout},
+    grammar::Sym,
+    table::lrtable::Table,
+};
+
+/// C header and source of parser with the table of `table`, every
+/// identifier starts with `prefix` (`PREFIX` for macros). The driver
+/// is a reentrant push parser that uses only the stack given by the
+/// caller, values are `PREFIX_VALUE` (`intptr_t` by default).
+pub fn emit_c(table: &dyn Table, prefix: &str) -> (String, String) {
+    let layout = Layout::new(table);
+    (header(&layout, prefix), source(&layout, prefix))
+}
+
+fn header(layout: &Layout, prefix: &str) -> String {
+    let upper = prefix.to_uppercase();
+    let mut tokens = String::new();
+    for (i, c) in layout.terms.iter().enumerate() {
+        let name = format!("{}_TOKEN_{}", upper, token_name(*c).to_uppercase());
+        tokens.push_str(&format!(
+            "    {} = {}, /* {} */\n",
+            name,
+            i,
+            Sym::Normal(*c)
+        ));
+    }
+    tokens.push_str(&format!(
+        "    {}_TOKEN_END = {} /* end of input */\n",
+        upper,
+        layout.terms.len()
+    ));
+    let max_len = layout.rules.iter().map(|x| x.1).max().unwrap_or(0).max(1);
+    HEADER
+        .replace("$COMMENT", &layout.block_comment())
+        .replace("$TOKENS", &tokens)
+        .replace("$MAX_LEN", &max_len.to_string())
+        .replace("$prefix", prefix)
+        .replace("$PREFIX", &upper)
+}
+
+fn source(layout: &Layout, prefix: &str) -> String {
+    let mut tables = String::new();
+    let codes: Vec<String> = layout
+        .terms
+        .iter()
+        .map(|x| (*x as u32).to_string())
+        .collect();
+    tables.push_str(&format!(
+        "/* code points of tokens */\nstatic const long $prefix_terms[{}] = {{{}}};\n\n",
+        codes.len().max(1),
+        codes.join(", ")
+    ));
+
+    tables.push_str(&format!(
+        "/* shift to s is s + 1, reduction by r is -(r + 1), accept is -1, error 0 */\n\
+         static const int $prefix_action[{}][{}] = {{\n",
+        layout.actions.len(),
+        layout.terms.len() + 1
+    ));
+    for row in layout.action_codes() {
+        let cells: Vec<String> = row.iter().map(|x| x.to_string()).collect();
+        tables.push_str(&format!("    {{{}}},\n", cells.join(", ")));
+    }
+    tables.push_str("};\n\n");
+
+    tables.push_str(&format!(
+        "/* -1 where there is no goto */\nstatic const int $prefix_goto[{}][{}] = {{\n",
+        layout.gotos.len(),
+        layout.non_terms.len()
+    ));
+    for row in &layout.gotos {
+        let cells: Vec<String> = row
+            .iter()
+            .map(|x| x.map_or("-1".to_string(), |x| x.to_string()))
+            .collect();
+        tables.push_str(&format!("    {{{}}},\n", cells.join(", ")));
+    }
+    tables.push_str("};\n\n");
+
+    let lens: Vec<String> = layout.rules.iter().map(|x| x.1.to_string()).collect();
+    let lefts: Vec<String> = layout.rules.iter().map(|x| x.0.to_string()).collect();
+    tables.push_str(&format!(
+        "static const int $prefix_rule_len[{}] = {{{}}};\n",
+        lens.len(),
+        lens.join(", ")
+    ));
+    tables.push_str(&format!(
+        "/* index of left side in goto columns */\nstatic const int $prefix_rule_left[{}] = {{{}}};\n",
+        lefts.len(),
+        lefts.join(", ")
+    ));
+
+    SOURCE
+        .replace("$COMMENT", &layout.block_comment())
+        .replace("$TABLES", &tables)
+        .replace("$STATES", &layout.actions.len().to_string())
+        .replace("$TERMS", &layout.terms.len().to_string())
+        .replace("$prefix", prefix)
+        .replace("$PREFIX", &prefix.to_uppercase())
+}
+
+const HEADER: &str = r#"$COMMENT
+#ifndef $PREFIX_H
+#define $PREFIX_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+/* type of semantic values, define it before including this header */
+#ifndef $PREFIX_VALUE
+#define $PREFIX_VALUE intptr_t
+#endif
+
+typedef $PREFIX_VALUE $prefix_value;
+
+enum $prefix_token {
+$TOKENS};
+
+enum $prefix_status {
+    $PREFIX_MORE = 0,          /* token was shifted, push next one */
+    $PREFIX_ACCEPTED = 1,      /* value of starting rule is in result */
+    $PREFIX_SYNTAX_ERROR = -1, /* state of the error is in error_state */
+    $PREFIX_STACK_OVERFLOW = -2
+};
+
+/* longest right side of rule, size of values given to reduce */
+#define $PREFIX_MAX_RULE_LEN $MAX_LEN
+
+typedef struct $prefix_entry {
+    int state;
+    $prefix_value value;
+} $prefix_entry;
+
+/* value of shifted token, zero when NULL */
+typedef void (*$prefix_shift_fn)(void *user, int token, size_t offset, $prefix_value *result);
+/* value of reduced rule from values of its right side, first value
+   (or zero) when NULL */
+typedef void (*$prefix_reduce_fn)(void *user, int rule, $prefix_value *values, int len,
+                                  $prefix_value *result);
+
+typedef struct $prefix_parser {
+    $prefix_entry *stack;
+    size_t capacity;
+    size_t depth;
+    $prefix_shift_fn shift;
+    $prefix_reduce_fn reduce;
+    void *user;
+    $prefix_value result;
+    int error_state;
+} $prefix_parser;
+
+/* parser using caller's stack of capacity entries (at least 1) */
+void $prefix_init($prefix_parser *parser, $prefix_entry *stack, size_t capacity,
+                  $prefix_shift_fn shift, $prefix_reduce_fn reduce, void *user);
+
+/* next token found at offset, $PREFIX_TOKEN_END at the end of input;
+   the token was not shifted when it returns $PREFIX_ACCEPTED (only
+   LR(0) tables accept before the end of input) */
+int $prefix_push($prefix_parser *parser, int token, size_t offset);
+
+/* token of code point or -1 when it is not a terminal */
+int $prefix_token_of(long c);
+
+/* nonzero when token has action in state, for error messages */
+int $prefix_expects(int state, int token);
+
+#endif
+"#;
+
+const SOURCE: &str = r#"$COMMENT
+#include <string.h>
+
+#include "$prefix.h"
+
+$TABLES
+void $prefix_init($prefix_parser *parser, $prefix_entry *stack, size_t capacity,
+                  $prefix_shift_fn shift, $prefix_reduce_fn reduce, void *user)
+{
+    memset(parser, 0, sizeof *parser);
+    parser->stack = stack;
+    parser->capacity = capacity;
+    parser->shift = shift;
+    parser->reduce = reduce;
+    parser->user = user;
+    parser->depth = 1;
+    memset(&stack[0], 0, sizeof stack[0]);
+}
+
+int $prefix_token_of(long c)
+{
+    int i;
+    for (i = 0; i < $TERMS; i++) {
+        if ($prefix_terms[i] == c) {
+            return i;
+        }
+    }
+    return -1;
+}
+
+int $prefix_expects(int state, int token)
+{
+    if (state < 0 || state >= $STATES || token < 0 || token > $TERMS) {
+        return 0;
+    }
+    /* error terminal of recovery rules is never in the input */
+    if (token < $TERMS && $prefix_terms[token] == 0xE000) {
+        return 0;
+    }
+    return $prefix_action[state][token] != 0;
+}
+
+int $prefix_push($prefix_parser *parser, int token, size_t offset)
+{
+    $prefix_value values[$PREFIX_MAX_RULE_LEN];
+    $prefix_value value;
+    int state, action, rule, len, target, i;
+
+    for (;;) {
+        state = parser->stack[parser->depth - 1].state;
+        action = token < 0 || token > $TERMS ? 0 : $prefix_action[state][token];
+        if (action > 0 && token != $PREFIX_TOKEN_END) {
+            if (parser->depth == parser->capacity) {
+                return $PREFIX_STACK_OVERFLOW;
+            }
+            memset(&value, 0, sizeof value);
+            if (parser->shift) {
+                parser->shift(parser->user, token, offset, &value);
+            }
+            parser->stack[parser->depth].state = action - 1;
+            parser->stack[parser->depth].value = value;
+            parser->depth++;
+            return $PREFIX_MORE;
+        }
+        if (action >= 0) {
+            parser->error_state = state;
+            return $PREFIX_SYNTAX_ERROR;
+        }
+
+        rule = -action - 1;
+        len = $prefix_rule_len[rule];
+        if ((size_t)len >= parser->depth) {
+            parser->error_state = state;
+            return $PREFIX_SYNTAX_ERROR;
+        }
+        /* goto and room for it are checked before the stack is touched */
+        target = 0;
+        if (rule != 0) {
+            target = $prefix_goto[parser->stack[parser->depth - len - 1].state][$prefix_rule_left[rule]];
+            if (target < 0) {
+                parser->error_state = state;
+                return $PREFIX_SYNTAX_ERROR;
+            }
+            if (len == 0 && parser->depth == parser->capacity) {
+                return $PREFIX_STACK_OVERFLOW;
+            }
+        }
+        for (i = 0; i < len; i++) {
+            values[i] = parser->stack[parser->depth - len + i].value;
+        }
+        parser->depth -= len;
+        memset(&value, 0, sizeof value);
+        if (parser->reduce) {
+            parser->reduce(parser->user, rule, values, len, &value);
+        } else if (len > 0) {
+            value = values[0];
+        }
+        if (rule == 0) {
+            parser->result = value;
+            return $PREFIX_ACCEPTED;
+        }
+        parser->stack[parser->depth].state = target;
+        parser->stack[parser->depth].value = value;
+        parser->depth++;
+    }
+}
+"#;
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, process::Command};
+
+    use crate::{
+        codegen::tests::{check_output, interpreted},
+        grammar::Grammar,
+        table::algo::Algo,
+        testing::{inputs, tables},
+    };
+
+    use super::*;
+
+    // Prints reductions of accepted input or offset of error
+    // for `prefix input` lines, every parser has own RUN
+    const MAIN: &str = r#"#include <stdio.h>
+#include <string.h>
+
+$INCLUDES
+struct rules {
+    int all[256];
+    int len;
+};
+
+static void record(void *user, int rule, intptr_t *values, int len, intptr_t *result)
+{
+    struct rules *rules = user;
+    (void)values;
+    (void)len;
+    (void)result;
+    rules->all[rules->len++] = rule;
+}
+
+#define RUN(P, U)                                                               \
+    static void run_##P(const char *input)                                      \
+    {                                                                           \
+        P##_entry stack[64];                                                    \
+        P##_parser parser;                                                      \
+        struct rules rules;                                                     \
+        size_t i, n = strlen(input);                                            \
+        int res = U##_MORE, early = 0;                                          \
+        long c;                                                                 \
+        rules.len = 0;                                                          \
+        P##_init(&parser, stack, 64, NULL, record, &rules);                     \
+        for (i = 0; i <= n && res == U##_MORE; i++) {                           \
+            int token = i == n ? U##_TOKEN_END : P##_token_of(input[i]);        \
+            res = P##_push(&parser, token, i);                                  \
+            if (res == U##_ACCEPTED && i < n) {                                 \
+                res = U##_SYNTAX_ERROR;                                         \
+                early = 1;                                                      \
+            }                                                                   \
+        }                                                                       \
+        if (res != U##_ACCEPTED) {                                              \
+            printf("error %lu [", (unsigned long)(i - 1));                      \
+            for (c = 1; c < 128 && !early; c++) {                               \
+                if (P##_expects(parser.error_state, P##_token_of(c))) {         \
+                    printf("%c", (char)c);                                      \
+                }                                                               \
+            }                                                                   \
+            /* error terminal of recovery rules is never expected */           \
+            if (!early && P##_expects(parser.error_state, P##_token_of(0xE000))) { \
+                printf("error");                                                \
+            }                                                                   \
+            if (early || P##_expects(parser.error_state, U##_TOKEN_END)) {      \
+                printf("$");                                                    \
+            }                                                                   \
+            printf("]\n");                                                      \
+            return;                                                             \
+        }                                                                       \
+        printf("ok");                                                           \
+        for (i = 0; i < (size_t)rules.len; i++) {                               \
+            printf(" %d", rules.all[i]);                                        \
+        }                                                                       \
+        printf("\n");                                                           \
+    }
+
+$RUNS
+int main(void)
+{
+    char line[256];
+    while (fgets(line, sizeof line, stdin)) {
+        char *input = strchr(line, ' ') + 1;
+        input[strcspn(input, "\n")] = 0;
+        *(input - 1) = 0;
+$DISPATCH    }
+    return 0;
+}
+"#;
+
+    #[test]
+    fn compiled() {
+        let dir = std::env::temp_dir().join(format!("lr0_gen_c_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let mut includes = String::new();
+        let mut runs = String::new();
+        let mut dispatch = String::new();
+        let mut sources = vec!["main.c".to_string()];
+        let mut stdin = String::new();
+        let mut expected = String::new();
+        for (prefix, _, table) in tables() {
+            let (h, c) = emit_c(table.as_ref(), &prefix);
+            fs::write(dir.join(format!("{}.h", prefix)), h).unwrap();
+            fs::write(dir.join(format!("{}.c", prefix)), c).unwrap();
+            sources.push(format!("{}.c", prefix));
+            includes.push_str(&format!("#include \"{}.h\"\n", prefix));
+            runs.push_str(&format!("RUN({}, {})\n", prefix, prefix.to_uppercase()));
+            dispatch.push_str(&format!(
+                "        if (strcmp(line, \"{}\") == 0) run_{}(input);\n",
+                prefix, prefix
+            ));
+            for input in inputs(table.grammar()) {
+                stdin.push_str(&format!("{} {}\n", prefix, input));
+                expected.push_str(&format!("{}\n", interpreted(table.as_ref(), &input)));
+            }
+        }
+        let main = MAIN
+            .replace("$INCLUDES", &includes)
+            .replace("$RUNS", &runs)
+            .replace("$DISPATCH", &dispatch);
+        fs::write(dir.join("main.c"), main).unwrap();
+
+        let out = Command::new("cc")
+            .args([
+                "-std=c99",
+                "-Wall",
+                "-Wextra",
+                "-pedantic",
+                "-Werror",
+                "-o",
+                "main",
+            ])
+            .args(&sources)
+            .current_dir(&dir)
+            .output()
+            .unwrap();
+        assert!(
+            out.status.success(),
+            "{}",
+            String::from_utf8_lossy(&out.stderr)
+        );
+        check_output(Command::new(dir.join("main")), &stdin, &expected);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    // reduction by empty rule needs a free entry for its goto
+    const OVERFLOW: &str = r#"#include <stdio.h>
+
+#include "simple.h"
+
+int main(void)
+{
+    simple_entry stack[3];
+    simple_parser parser;
+    int a, b;
+    stack[2].state = -1;
+    simple_init(&parser, stack, 2, NULL, NULL, NULL);
+    a = simple_push(&parser, simple_token_of('a'), 0);
+    b = simple_push(&parser, simple_token_of('b'), 1);
+    printf("%d %d %lu %d\n", a, b, (unsigned long)parser.depth, stack[2].state);
+    return 0;
+}
+"#;
+
+    #[test]
+    fn overflow() {
+        let dir = std::env::temp_dir().join(format!("lr0_gen_c_overflow_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let g = Grammar::from_text(include_str!("../../examples/simple.gram")).unwrap();
+        let (h, c) = emit_c(Algo::LR1.build(&g).as_ref(), "simple");
+        fs::write(dir.join("simple.h"), h).unwrap();
+        fs::write(dir.join("simple.c"), c).unwrap();
+        fs::write(dir.join("main.c"), OVERFLOW).unwrap();
+
+        let out = Command::new("cc")
+            .args([
+                "-std=c99",
+                "-Wall",
+                "-Wextra",
+                "-pedantic",
+                "-Werror",
+                "-o",
+                "main",
+            ])
+            .args(["main.c", "simple.c"])
+            .current_dir(&dir)
+            .output()
+            .unwrap();
+        assert!(
+            out.status.success(),
+            "{}",
+            String::from_utf8_lossy(&out.stderr)
+        );
+        let out = Command::new(dir.join("main")).output().unwrap();
+        // MORE, STACK_OVERFLOW, stack left as it was
+        assert_eq!(String::from_utf8_lossy(&out.stdout), "0 -2 2 -1\n");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
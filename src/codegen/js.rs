@@ -0,0 +1,333 @@
+use crate::{codegen::Layout, grammar::ERROR, json, table::lrtable::Table};
+
+/// ES module with the table of `table` and its driver, and TypeScript
+/// typings (`.d.ts`) of the module. Default hooks build the same tree as
+/// `TreeBuilder`, `error` hook gets syntax errors instead of throwing.
+pub fn emit_js(table: &dyn Table) -> (String, String) {
+    let layout = Layout::new(table);
+    let comment = layout.block_comment();
+
+    let strings = |x: &[char]| json::array(x.iter().map(|c| json::string(&c.to_string())));
+    let rows = |rows: Vec<Vec<i64>>| {
+        let rows: Vec<String> = rows
+            .iter()
+            .map(|r| {
+                let cells: Vec<String> = r.iter().map(|x| x.to_string()).collect();
+                format!("  [{}],\n", cells.join(", "))
+            })
+            .collect();
+        format!("[\n{}]", rows.concat())
+    };
+    let gotos = layout
+        .gotos
+        .iter()
+        .map(|r| r.iter().map(|x| x.map_or(-1, |x| x as i64)).collect())
+        .collect();
+    let lens: Vec<String> = layout.rules.iter().map(|x| x.1.to_string()).collect();
+    let lefts: Vec<String> = layout.rules.iter().map(|x| x.0.to_string()).collect();
+
+    let module = MODULE
+        .replace("$COMMENT", &comment)
+        .replace("$TERMS", &strings(&layout.terms))
+        .replace("$NON_TERMS", &strings(&layout.non_terms))
+        .replace(
+            "$RULES",
+            &json::array(layout.rule_text.iter().map(|x| json::string(x))),
+        )
+        .replace("$ERROR", &json::string(&ERROR.to_string()))
+        .replace("$ACTION", &rows(layout.action_codes()))
+        .replace("$GOTO", &rows(gotos))
+        .replace("$RULE_LEN", &format!("[{}]", lens.join(", ")))
+        .replace("$RULE_LEFT", &format!("[{}]", lefts.join(", ")));
+    (module, TYPINGS.replace("$COMMENT", &comment))
+}
+
+const MODULE: &str = r#"$COMMENT
+/** terminals, index of terminal is its column in ACTION */
+export const TERMS = $TERMS;
+/** non-terminals, index is column in GOTO */
+export const NON_TERMS = $NON_TERMS;
+/** text of rules, rule 0 is the starting one */
+export const RULES = $RULES;
+/** terminal used by rules with error */
+export const ERROR = $ERROR;
+
+// shift to s is s + 1, reduction by r is -(r + 1), accept is -1, error 0,
+// last column is the end of input
+const ACTION = $ACTION;
+// -1 where there is no goto
+const GOTO = $GOTO;
+const RULE_LEN = $RULE_LEN;
+const RULE_LEFT = $RULE_LEFT;
+
+const COLUMN = new Map(TERMS.map((x, i) => [x, i]));
+
+function describe(symbol) {
+  return symbol === null ? "end of input" : `'${symbol}'`;
+}
+
+/** Syntax error, found is null at the end of input */
+export class ParseError extends Error {
+  constructor(offset, found, state, expected) {
+    let message = `unexpected ${describe(found)} at ${offset} in state ${state}`;
+    if (expected.length > 0) {
+      message += `, expected ${expected.map(describe).join(", ")}`;
+    }
+    super(message);
+    this.name = "ParseError";
+    this.offset = offset;
+    this.found = found;
+    this.state = state;
+    this.expected = expected;
+  }
+}
+
+/** Hooks building the same tree as TreeBuilder of lr0_gen */
+export const treeBuilder = {
+  shift: (symbol, offset) => ({ symbol, offset }),
+  reduce: (rule, symbol, children) => ({ symbol, rule, children }),
+};
+
+/** Tree as s-expression, same as ParseTree::sexp */
+export function sexp(tree) {
+  if (tree.children === undefined) {
+    return tree.symbol === ERROR ? "error" : tree.symbol;
+  }
+  return `(${[tree.symbol, ...tree.children.map(sexp)].join(" ")})`;
+}
+
+/** Table driven parser, symbols are pushed one by one and the end of input is given by finish */
+export class Parser {
+  constructor(hooks = treeBuilder) {
+    this.hooks = hooks;
+    this.states = [0];
+    // values for symbols on stack (without bottom state)
+    this.values = [];
+  }
+
+  /**
+   * Returns { value } when the starting rule was accepted before the symbol
+   * (it can happen only in LR(0) tables), otherwise null
+   */
+  push(symbol, offset) {
+    return this.advance(symbol, offset);
+  }
+
+  /** End of input at offset, gives value of the starting rule */
+  finish(offset) {
+    return this.advance(null, offset).value;
+  }
+
+  state() {
+    return this.states[this.states.length - 1];
+  }
+
+  // reductions until the symbol is shifted or the input accepted
+  advance(symbol, offset) {
+    const column = symbol === null ? TERMS.length : COLUMN.get(symbol);
+    for (;;) {
+      const state = this.state();
+      const action = column === undefined ? 0 : ACTION[state][column];
+      if (action > 0 && symbol !== null) {
+        this.states.push(action - 1);
+        this.values.push(this.hooks.shift(symbol, offset));
+        return null;
+      }
+      if (action >= 0) {
+        throw this.error(state, symbol, offset);
+      }
+      const rule = -action - 1;
+      const len = RULE_LEN[rule];
+      if (len >= this.states.length) {
+        throw this.error(state, symbol, offset);
+      }
+      // goto is checked before the stacks change or hooks run
+      const below = this.states[this.states.length - 1 - len];
+      const target = rule === 0 ? 0 : GOTO[below][RULE_LEFT[rule]];
+      if (target < 0) {
+        throw this.error(state, symbol, offset);
+      }
+      const values = this.values.splice(this.values.length - len, len);
+      this.states.length -= len;
+      const value = this.hooks.reduce(rule, NON_TERMS[RULE_LEFT[rule]], values);
+      if (rule === 0) {
+        return { value };
+      }
+      this.states.push(target);
+      this.values.push(value);
+    }
+  }
+
+  error(state, found, offset) {
+    const expected = [];
+    ACTION[state].forEach((x, i) => {
+      // error terminal of recovery rules is never in the input
+      if (x !== 0 && TERMS[i] !== ERROR) {
+        expected.push(i < TERMS.length ? TERMS[i] : null);
+      }
+    });
+    return new ParseError(offset, found, state, expected);
+  }
+}
+
+/**
+ * Parses whole input, offsets are in code points. Input continuing after the
+ * starting rule was accepted is an error. Errors are thrown unless hooks have error.
+ */
+export function parse(input, hooks = treeBuilder) {
+  const parser = new Parser(hooks);
+  const symbols = Array.from(input);
+  try {
+    symbols.forEach((symbol, offset) => {
+      if (parser.push(symbol, offset) !== null) {
+        throw new ParseError(offset, symbol, parser.state(), [null]);
+      }
+    });
+    return parser.finish(symbols.length);
+  } catch (e) {
+    if (e instanceof ParseError && hooks.error) {
+      return hooks.error(e);
+    }
+    throw e;
+  }
+}
+"#;
+
+const TYPINGS: &str = r#"$COMMENT
+export declare const TERMS: readonly string[];
+export declare const NON_TERMS: readonly string[];
+export declare const RULES: readonly string[];
+export declare const ERROR: string;
+
+export declare class ParseError extends Error {
+  readonly offset: number;
+  /** null at the end of input */
+  readonly found: string | null;
+  readonly state: number;
+  readonly expected: (string | null)[];
+}
+
+export interface Leaf {
+  symbol: string;
+  offset: number;
+}
+
+export interface Node {
+  symbol: string;
+  rule: number;
+  children: Tree[];
+}
+
+export type Tree = Leaf | Node;
+
+export interface Hooks<V> {
+  shift(symbol: string, offset: number): V;
+  reduce(rule: number, left: string, values: V[]): V;
+  /** value returned by parse instead of throwing the error */
+  error?(error: ParseError): V;
+}
+
+export declare const treeBuilder: Hooks<Tree>;
+
+export declare function sexp(tree: Tree): string;
+
+export declare class Parser<V = Tree> {
+  constructor(hooks?: Hooks<V>);
+  push(symbol: string, offset: number): { value: V } | null;
+  finish(offset: number): V;
+  state(): number;
+}
+
+export declare function parse(input: string): Tree;
+export declare function parse<V>(input: string, hooks: Hooks<V>): V;
+"#;
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, process::Command};
+
+    use crate::{
+        codegen::tests::{check_output, interpreted},
+        stackautomata::StackAutomata,
+        testing::{inputs, tables},
+    };
+
+    use super::*;
+
+    // tree of accepted input or offset of error, error hook is used
+    const MAIN: &str = r#"import { readFileSync } from "fs";
+$IMPORTS
+const modules = { $MODULES };
+const out = [];
+for (const line of readFileSync(0, "utf8").split("\n")) {
+  if (line === "") {
+    continue;
+  }
+  const space = line.indexOf(" ");
+  const m = modules[line.slice(0, space)];
+  const res = m.parse(line.slice(space + 1), { ...m.treeBuilder, error: (e) => `error ${e.offset} [${e.expected.map((x) => x ?? "$").join("")}]` });
+  out.push(typeof res === "string" ? res : `ok ${m.sexp(res)}`);
+}
+process.stdout.write(out.join("\n") + "\n");
+"#;
+
+    // first JS runtime found
+    fn runtime() -> Option<Command> {
+        for (name, args) in [
+            ("node", vec![]),
+            ("bun", vec![]),
+            ("deno", vec!["run", "-A"]),
+        ] {
+            let found = Command::new(name).arg("--version").output();
+            if found.map(|x| x.status.success()).unwrap_or(false) {
+                let mut res = Command::new(name);
+                res.args(args);
+                return Some(res);
+            }
+        }
+        None
+    }
+
+    #[test]
+    fn run() {
+        let Some(mut command) = runtime() else {
+            eprintln!("no JS runtime found, generated module is not tested");
+            return;
+        };
+        let dir = std::env::temp_dir().join(format!("lr0_gen_js_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let mut imports = String::new();
+        let mut modules = vec![];
+        let mut stdin = String::new();
+        let mut expected = String::new();
+        for (module, _, table) in tables() {
+            let (js, ts) = emit_js(table.as_ref());
+            fs::write(dir.join(format!("{}.mjs", module)), js).unwrap();
+            fs::write(dir.join(format!("{}.d.mts", module)), ts).unwrap();
+            imports.push_str(&format!(
+                "import * as {} from \"./{}.mjs\";\n",
+                module, module
+            ));
+            for input in inputs(table.grammar()) {
+                stdin.push_str(&format!("{} {}\n", module, input));
+                let res = match interpreted(table.as_ref(), &input) {
+                    x if x.starts_with("ok") => {
+                        let tree = StackAutomata::new(table.as_ref(), &input).run().unwrap();
+                        format!("ok {}", tree.sexp())
+                    }
+                    x => x,
+                };
+                expected.push_str(&format!("{}\n", res));
+            }
+            modules.push(module);
+        }
+        let main = MAIN
+            .replace("$IMPORTS", &imports)
+            .replace("$MODULES", &modules.join(", "));
+        fs::write(dir.join("main.mjs"), main).unwrap();
+
+        command.arg(dir.join("main.mjs"));
+        check_output(command, &stdin, &expected);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
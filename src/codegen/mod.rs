@@ -0,0 +1,292 @@
+use crate::{
+    grammar::{Sym, ERROR},
+    table::lrtable::{lookahead, Action, Table},
+};
+
+pub mod build;
+pub mod c;
+pub mod js;
+pub mod rust;
+
+/// Action of a cell with the target state of shift resolved
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Cell {
+    Error,
+    Shift(usize),
+    Reduce(usize),
+    Accept,
+}
+
+// Table flattened into arrays for emitters, terminals and
+// non-terminals are sorted, last action column is end of input.
+// Conflicting cells and shifts without target are errors
+// same as in StackAutomata.
+pub(crate) struct Layout {
+    pub(crate) name: String,
+    pub(crate) terms: Vec<char>,
+    pub(crate) non_terms: Vec<char>,
+    pub(crate) actions: Vec<Vec<Cell>>,
+    pub(crate) gotos: Vec<Vec<Option<usize>>>,
+    // index of left side in non_terms and length of right side
+    pub(crate) rules: Vec<(usize, usize)>,
+    pub(crate) rule_text: Vec<String>,
+    pub(crate) conflicts: usize,
+}
+
+impl Layout {
+    pub(crate) fn new(table: &dyn Table) -> Self {
+        let g = table.grammar();
+        let mut terms: Vec<char> = g.terms().iter().copied().collect();
+        terms.sort();
+        let mut non_terms: Vec<char> = g.non_terms().iter().copied().collect();
+        non_terms.sort();
+
+        let mut conflicts = 0;
+        let mut actions = vec![];
+        let mut gotos = vec![];
+        for state in 0..table.states() {
+            let row: Vec<Cell> = lookahead(g)
+                .into_iter()
+                .map(|s| match table.get_action(state, s) {
+                    Some(Action::Shift) => match s {
+                        Sym::Normal(c) => table.get_goto(state, c).map_or(Cell::Error, Cell::Shift),
+                        Sym::Eps => Cell::Error,
+                    },
+                    Some(Action::Reduction(r)) => Cell::Reduce(r),
+                    Some(Action::Accept) => Cell::Accept,
+                    Some(Action::Error) => {
+                        conflicts += 1;
+                        Cell::Error
+                    }
+                    Some(Action::Empty) | None => Cell::Error,
+                })
+                .collect();
+            actions.push(row);
+            gotos.push(
+                non_terms
+                    .iter()
+                    .map(|c| table.get_goto(state, *c))
+                    .collect(),
+            );
+        }
+
+        let rules = g
+            .rules()
+            .iter()
+            .map(|r| {
+                let left = non_terms.binary_search(&r.left()).unwrap();
+                (left, r.right().len())
+            })
+            .collect();
+        Self {
+            name: table.name().to_string(),
+            terms,
+            non_terms,
+            actions,
+            gotos,
+            rules,
+            rule_text: g.rules().iter().map(|r| r.to_string()).collect(),
+            conflicts,
+        }
+    }
+
+    // header comment lines shared by emitters
+    pub(crate) fn describe(&self) -> Vec<String> {
+        let mut res = vec![
+            format!(
+                "Parser generated by lr0_gen from {}, do not edit.",
+                self.name
+            ),
+            String::new(),
+            "Rules:".to_string(),
+        ];
+        for (i, r) in self.rule_text.iter().enumerate() {
+            res.push(format!("  {} : {}", i, r));
+        }
+        if self.conflicts > 0 {
+            res.push(String::new());
+            res.push(format!(
+                "Table has {} conflicting cells, they are syntax errors.",
+                self.conflicts
+            ));
+        }
+        res
+    }
+
+    // describe() as /* */ comment, C and JavaScript share it
+    pub(crate) fn block_comment(&self) -> String {
+        let mut res = "/*\n".to_string();
+        for line in self.describe() {
+            match line.is_empty() {
+                true => res.push_str(" *\n"),
+                false => res.push_str(&format!(" * {}\n", line)),
+            }
+        }
+        res.push_str(" */\n");
+        res
+    }
+
+    // actions as numbers: shift to s is s + 1, reduction by r
+    // is -(r + 1), accept is -1 and error 0
+    pub(crate) fn action_codes(&self) -> Vec<Vec<i64>> {
+        self.actions
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|x| match x {
+                        Cell::Error => 0,
+                        Cell::Shift(s) => *s as i64 + 1,
+                        Cell::Reduce(r) => -(*r as i64) - 1,
+                        Cell::Accept => -1,
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+// identifier of terminal in generated code
+pub(crate) fn token_name(c: char) -> String {
+    let name = match c {
+        ERROR => "Error",
+        '+' => "Plus",
+        '-' => "Minus",
+        '*' => "Star",
+        '/' => "Slash",
+        '%' => "Percent",
+        '^' => "Caret",
+        '=' => "Eq",
+        '<' => "Lt",
+        '>' => "Gt",
+        '!' => "Bang",
+        '?' => "Question",
+        '&' => "Amp",
+        '|' => "Pipe",
+        '~' => "Tilde",
+        '(' => "LParen",
+        ')' => "RParen",
+        '[' => "LBracket",
+        ']' => "RBracket",
+        '{' => "LBrace",
+        '}' => "RBrace",
+        ',' => "Comma",
+        '.' => "Dot",
+        ':' => "Colon",
+        ';' => "Semi",
+        '\'' => "Quote",
+        '"' => "DoubleQuote",
+        '#' => "Hash",
+        '$' => "Dollar",
+        '@' => "At",
+        '_' => "Underscore",
+        '\\' => "Backslash",
+        _ => "",
+    };
+    match c {
+        _ if !name.is_empty() => name.to_string(),
+        'a'..='z' => c.to_ascii_uppercase().to_string(),
+        'A'..='Z' => format!("Upper{}", c),
+        '0'..='9' => format!("D{}", c),
+        _ => format!("U{:04X}", c as u32),
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use std::{
+        io::Write,
+        process::{Command, Stdio},
+    };
+
+    use crate::{grammar::Grammar, stackautomata::StackAutomata, table::algo::Algo};
+
+    use super::*;
+
+    // reductions of accepted input or offset of error with expected
+    // terminals, end of input is $
+    pub(crate) fn interpreted(table: &dyn Table, input: &str) -> String {
+        let mut autom = StackAutomata::new(table, input);
+        autom.enable_trace();
+        match autom.run() {
+            Ok(_) if autom.consumed() => {
+                let rules: Vec<String> = autom.result().iter().map(|x| x.to_string()).collect();
+                format!("ok {}", rules.join(" "))
+            }
+            // accepted before the end, only end of input was expected
+            Ok(_) => {
+                let left = autom.trace().unwrap().steps.last().unwrap().input.len();
+                format!("error {} [$]", input.chars().count() - left)
+            }
+            Err(e) => {
+                let expected: String = e
+                    .expected
+                    .iter()
+                    .map(|x| match x {
+                        Sym::Normal(c) => *c,
+                        Sym::Eps => '$',
+                    })
+                    .collect();
+                format!("error {} [{}]", e.offset, expected)
+            }
+        }
+    }
+
+    // runs generated parser with `module input` lines on stdin
+    // and compares its output lines with expected ones
+    pub(crate) fn check_output(mut command: Command, stdin: &str, expected: &str) {
+        let mut child = command
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+        child
+            .stdin
+            .take()
+            .unwrap()
+            .write_all(stdin.as_bytes())
+            .unwrap();
+        let out = child.wait_with_output().unwrap();
+        let out = String::from_utf8(out.stdout).unwrap();
+        for ((line, want), got) in stdin.lines().zip(expected.lines()).zip(out.lines()) {
+            assert_eq!(got, want, "{}", line);
+        }
+        assert_eq!(out.lines().count(), expected.lines().count());
+    }
+
+    #[test]
+    fn layout() {
+        let g = Grammar::from_text(include_str!("../../examples/simple.gram")).unwrap();
+        let layout = Layout::new(Algo::LR1.build(&g).as_ref());
+        assert_eq!(layout.terms, vec!['a', 'b']);
+        assert_eq!(layout.non_terms, vec!['A', 'S']);
+        assert_eq!(layout.rules, vec![(1, 1), (0, 3), (0, 0)]);
+        assert_eq!(
+            layout.actions[0],
+            vec![Cell::Shift(2), Cell::Error, Cell::Reduce(2)]
+        );
+        assert_eq!(layout.gotos[0], vec![Some(1), None]);
+        assert_eq!(
+            layout.actions[1],
+            vec![Cell::Error, Cell::Error, Cell::Accept]
+        );
+        assert_eq!(layout.action_codes()[0], vec![3, 0, -3]);
+        assert_eq!(layout.action_codes()[1], vec![0, 0, -1]);
+        assert!(layout
+            .block_comment()
+            .starts_with("/*\n * Parser generated by lr0_gen from LR1Table, do not edit.\n *\n"));
+        assert_eq!(layout.conflicts, 0);
+
+        let layout = Layout::new(Algo::LR0.build(&g).as_ref());
+        assert!(layout.conflicts > 0);
+    }
+
+    #[test]
+    fn token_names() {
+        assert_eq!(token_name('a'), "A");
+        assert_eq!(token_name('A'), "UpperA");
+        assert_eq!(token_name('('), "LParen");
+        assert_eq!(token_name('7'), "D7");
+        assert_eq!(token_name(ERROR), "Error");
+        assert_eq!(token_name('λ'), "U03BB");
+    }
+}
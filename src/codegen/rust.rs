@@ -0,0 +1,468 @@
+use crate::{
+    codegen::{token_name, Cell, Layout},
+    grammar::ERROR,
+    table::lrtable::Table,
+};
+
+/// Self-contained Rust module with the table of `table` and its driver:
+/// `Token` enum of terminals, `Semantics` trait called on shift and
+/// reduction, push `Parser` and `parse` of whole string. The module
+/// uses only std and can be written to a file or `include!`d.
+pub fn emit_rust(table: &dyn Table) -> String {
+    let layout = Layout::new(table);
+    let mut out: String = layout
+        .describe()
+        .iter()
+        .map(|x| match x.is_empty() {
+            true => "//\n".to_string(),
+            false => format!("// {}\n", x),
+        })
+        .collect();
+    out.push_str("\nuse std::fmt;\n\n");
+    token(&mut out, &layout);
+    out.push_str(&driver(&layout));
+    tables(&mut out, &layout);
+    out
+}
+
+fn token(out: &mut String, layout: &Layout) {
+    let names: Vec<String> = layout.terms.iter().map(|x| token_name(*x)).collect();
+    out.push_str("/// Terminals of the grammar\n");
+    out.push_str("#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]\npub enum Token {\n");
+    for (c, name) in layout.terms.iter().zip(&names) {
+        match *c {
+            ERROR => out.push_str("    /// `error` of rules for recovery\n"),
+            c => out.push_str(&format!("    /// `{}`\n", c)),
+        }
+        out.push_str(&format!("    {},\n", name));
+    }
+    out.push_str("}\n\n");
+
+    let tokens: Vec<String> = names.iter().map(|x| format!("Token::{}", x)).collect();
+    out.push_str(&array("TOKENS", "Token", &tokens));
+    let chars: Vec<String> = layout.terms.iter().map(|x| format!("{:?}", x)).collect();
+    out.push_str(&array("TERMS", "char", &chars));
+    out.push('\n');
+
+    let display = match layout.terms.contains(&ERROR) {
+        true => "        match self {\n            Token::Error => write!(f, \"error\"),\n            _ => write!(f, \"{}\", self.as_char()),\n        }\n",
+        false => "        write!(f, \"{}\", self.as_char())\n",
+    };
+    out.push_str(&format!(
+        "impl Token {{
+    /// Token of a terminal of the grammar
+    pub fn from_char(c: char) -> Option<Token> {{
+        TERMS.iter().position(|x| *x == c).map(|x| TOKENS[x])
+    }}
+
+    pub fn as_char(self) -> char {{
+        TERMS[self as usize]
+    }}
+}}
+
+impl fmt::Display for Token {{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {{
+{}    }}
+}}
+",
+        display
+    ));
+}
+
+fn tables(out: &mut String, layout: &Layout) {
+    let cols = layout.terms.len() + 1;
+    out.push_str(&format!(
+        "\n// columns are TERMS and the end of input\nstatic ACTION: [[Action; {}]; {}] = [\n",
+        cols,
+        layout.actions.len()
+    ));
+    for row in &layout.actions {
+        let cells: Vec<String> = row
+            .iter()
+            .map(|x| match x {
+                Cell::Error => "Action::Error".to_string(),
+                Cell::Shift(s) => format!("Action::Shift({})", s),
+                Cell::Reduce(r) => format!("Action::Reduce({})", r),
+                Cell::Accept => "Action::Accept".to_string(),
+            })
+            .collect();
+        out.push_str(&format!("    [{}],\n", cells.join(", ")));
+    }
+    out.push_str("];\n\n");
+
+    // only reductions read GOTO
+    if layout
+        .actions
+        .iter()
+        .flatten()
+        .any(|x| matches!(x, Cell::Reduce(_)))
+    {
+        out.push_str(&format!(
+            "const NONE: usize = usize::MAX;\n\n// columns are NON_TERMS\nstatic GOTO: [[usize; {}]; {}] = [\n",
+            layout.non_terms.len(),
+            layout.gotos.len()
+        ));
+        for row in &layout.gotos {
+            let cells: Vec<String> = row
+                .iter()
+                .map(|x| x.map_or("NONE".to_string(), |x| x.to_string()))
+                .collect();
+            out.push_str(&format!("    [{}],\n", cells.join(", ")));
+        }
+        out.push_str("];\n\n");
+    }
+
+    let non_terms: Vec<String> = layout
+        .non_terms
+        .iter()
+        .map(|x| format!("{:?}", x))
+        .collect();
+    out.push_str(&array("NON_TERMS", "char", &non_terms));
+    out.push_str("\n// index of left side in NON_TERMS and length of right side\n");
+    let rules: Vec<String> = layout
+        .rules
+        .iter()
+        .map(|(l, n)| format!("({}, {})", l, n))
+        .collect();
+    out.push_str(&array("RULES", "(usize, usize)", &rules));
+}
+
+fn array(name: &str, ty: &str, items: &[String]) -> String {
+    format!(
+        "static {}: [{}; {}] = [{}];\n",
+        name,
+        ty,
+        items.len(),
+        items.join(", ")
+    )
+}
+
+// same for every grammar, tables are appended after it
+const DRIVER: &str = r#"
+/// Callbacks of the parser, values are kept on a stack next to the states,
+/// reduction gets values of the right side of the rule in order
+pub trait Semantics {
+    type Value;
+    fn on_shift(&mut self, token: Token, offset: usize) -> Self::Value;
+    fn on_reduce(&mut self, rule: usize, left: char, values: Vec<Self::Value>) -> Self::Value;
+}
+
+/// Syntax error, `None` stands for the end of input
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub offset: usize,
+    pub found: Option<char>,
+    pub state: usize,
+    /// terminals with non error action in state
+    pub expected: Vec<Option<char>>,
+}
+
+fn describe(c: &Option<char>) -> String {
+    match c {
+        Some(c) => format!("'{}'", c),
+        None => "end of input".to_string(),
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "unexpected {} at {} in state {}",
+            describe(&self.found),
+            self.offset,
+            self.state
+        )?;
+        if !self.expected.is_empty() {
+            let expected: Vec<String> = self.expected.iter().map(describe).collect();
+            write!(f, ", expected {}", expected.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Table driven parser, tokens are pushed one by one and
+/// the end of input is given by `finish`
+pub struct Parser<S: Semantics> {
+    states: Vec<usize>,
+    // values for symbols on stack (without bottom state)
+    values: Vec<S::Value>,
+    sem: S,
+}
+
+impl<S: Semantics> Parser<S> {
+    pub fn new(sem: S) -> Self {
+        Self {
+            states: vec![0],
+            values: vec![],
+            sem,
+        }
+    }
+
+    /// Value of the starting rule when it was accepted before the token
+    /// (it can happen only in LR(0) tables), otherwise `None`
+    pub fn push(&mut self, token: Token, offset: usize) -> Result<Option<S::Value>, ParseError> {
+        self.advance(Some(token), offset)
+    }
+
+    /// End of input at offset, gives value of the starting rule
+    pub fn finish(&mut self, offset: usize) -> Result<S::Value, ParseError> {
+        let state = self.state();
+        self.advance(None, offset)?
+            .ok_or_else(|| self.error(state, None, offset))
+    }
+
+    fn state(&self) -> usize {
+        self.states[self.states.len() - 1]
+    }
+
+    // reductions until the token is shifted or the input accepted
+    fn advance(&mut self, token: Option<Token>, offset: usize) -> Result<Option<S::Value>, ParseError> {
+        let column = token.map_or(TERMS.len(), |x| x as usize);
+        let found = token.map(Token::as_char);
+        loop {
+            let state = self.state();
+            match (ACTION[state][column], token) {
+$ARMS            }
+        }
+    }
+
+    fn error(&self, state: usize, found: Option<char>, offset: usize) -> ParseError {
+        let expected = ACTION[state]
+            .iter()
+            .enumerate()
+            .filter(|(_, x)| !x.is_error())
+            .map(|(i, _)| TERMS.get(i).copied())
+            // error terminal of recovery rules is never in the input
+            .filter(|x| *x != Some('\u{e000}'))
+            .collect();
+        ParseError {
+            offset,
+            found,
+            state,
+            expected,
+        }
+    }
+}
+
+/// Parses whole input, offsets are in characters. Input continuing
+/// after the starting rule was accepted is an error.
+pub fn parse<S: Semantics>(input: &str, sem: S) -> Result<S::Value, ParseError> {
+    let mut parser = Parser::new(sem);
+    let mut len = 0;
+    for (offset, c) in input.chars().enumerate() {
+        let state = parser.state();
+        let token = Token::from_char(c).ok_or_else(|| parser.error(state, Some(c), offset))?;
+        if parser.push(token, offset)?.is_some() {
+            return Err(ParseError {
+                offset,
+                found: Some(c),
+                state: parser.state(),
+                expected: vec![None],
+            });
+        }
+        len = offset + 1;
+    }
+    parser.finish(len)
+}
+
+$ACTION"#;
+
+// arms of advance for every kind of action, only kinds
+// the table has are emitted so none of them is dead code
+const ERROR_ARM: &str =
+    "                (Action::Error, _) => return Err(self.error(state, found, offset)),
+";
+
+const SHIFT_ARM: &str = "                (Action::Shift(target), Some(token)) => {
+                    self.states.push(target);
+                    let value = self.sem.on_shift(token, offset);
+                    self.values.push(value);
+                    return Ok(None);
+                }
+                (Action::Shift(_), None) => return Err(self.error(state, found, offset)),
+";
+
+// goto is looked up before the stack is touched, error leaves it as it was
+const REDUCE_ARM: &str = "                (Action::Reduce(rule), _) => {
+                    let (left, len) = RULES[rule];
+                    if len >= self.states.len() {
+                        return Err(self.error(state, found, offset));
+                    }
+                    let target = GOTO[self.states[self.states.len() - 1 - len]][left];
+                    if target == NONE {
+                        return Err(self.error(state, found, offset));
+                    }
+                    self.states.truncate(self.states.len() - len);
+                    self.states.push(target);
+                    let values = self.values.split_off(self.values.len() - len);
+                    let value = self.sem.on_reduce(rule, NON_TERMS[left], values);
+                    self.values.push(value);
+                }
+";
+
+const ACCEPT_ARM: &str = "                (Action::Accept, _) => {
+                    let len = RULES[0].1.min(self.values.len());
+                    let values = self.values.split_off(self.values.len() - len);
+                    return Ok(Some(self.sem.on_reduce(0, NON_TERMS[RULES[0].0], values)));
+                }
+";
+
+// driver with the match arms and Action enum of kinds in the table
+fn driver(layout: &Layout) -> String {
+    let used = |f: fn(&Cell) -> bool| layout.actions.iter().flatten().any(f);
+    let kinds = [
+        (used(|x| *x == Cell::Error), "Error", ERROR_ARM),
+        (
+            used(|x| matches!(x, Cell::Shift(_))),
+            "Shift(usize)",
+            SHIFT_ARM,
+        ),
+        (
+            used(|x| matches!(x, Cell::Reduce(_))),
+            "Reduce(usize)",
+            REDUCE_ARM,
+        ),
+        (used(|x| *x == Cell::Accept), "Accept", ACCEPT_ARM),
+    ];
+    let mut arms = String::new();
+    let mut action = "#[derive(Debug, Clone, Copy)]\nenum Action {\n".to_string();
+    for (_, variant, arm) in kinds.iter().filter(|x| x.0) {
+        arms.push_str(arm);
+        action.push_str(&format!("    {},\n", variant));
+    }
+    action.push_str("}\n\nimpl Action {\n    fn is_error(&self) -> bool {\n");
+    match kinds[0].0 {
+        true => action.push_str("        matches!(self, Action::Error)\n"),
+        false => action.push_str("        false\n"),
+    }
+    action.push_str("    }\n}\n");
+    DRIVER.replace("$ARMS", &arms).replace("$ACTION", &action)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, process::Command};
+
+    use crate::{
+        codegen::tests::{check_output, interpreted},
+        grammar::Grammar,
+        table::algo::Algo,
+        testing::{inputs, tables},
+    };
+
+    use super::*;
+
+    // Same output from generated modules, they are compiled
+    // into library with warnings denied
+    const HARNESS: &str = r#"
+pub struct Rules;
+
+macro_rules! semantics {
+    ($($m:ident),*) => {
+        $(impl $m::Semantics for Rules {
+            type Value = Vec<usize>;
+            fn on_shift(&mut self, _: $m::Token, _: usize) -> Vec<usize> {
+                vec![]
+            }
+            fn on_reduce(&mut self, rule: usize, _: char, values: Vec<Vec<usize>>) -> Vec<usize> {
+                let mut res: Vec<usize> = values.into_iter().flatten().collect();
+                res.push(rule);
+                res
+            }
+        })*
+
+        pub fn run(module: &str, input: &str) -> String {
+            let res = match module {
+                $(stringify!($m) => $m::parse(input, Rules).map_err(|e| {
+                    let expected: String = e.expected.iter().map(|x| x.unwrap_or('$')).collect();
+                    (e.offset, expected)
+                }),)*
+                _ => panic!("no module {}", module),
+            };
+            match res {
+                Ok(rules) => {
+                    let rules: Vec<String> = rules.iter().map(|x| x.to_string()).collect();
+                    format!("ok {}", rules.join(" "))
+                }
+                Err((offset, expected)) => format!("error {} [{}]", offset, expected),
+            }
+        }
+    };
+}
+"#;
+
+    const MAIN: &str = "use std::io::BufRead;
+
+fn main() {
+    for line in std::io::stdin().lock().lines() {
+        let line = line.unwrap();
+        let (module, input) = line.split_once(' ').unwrap();
+        println!(\"{}\", generated::run(module, input));
+    }
+}
+";
+
+    #[test]
+    fn compiled() {
+        let dir = std::env::temp_dir().join(format!("lr0_gen_rust_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let mut lib = String::new();
+        let mut modules = vec![];
+        let mut stdin = String::new();
+        let mut expected = String::new();
+        // only shift and accept, Action has no Reduce
+        let g = Grammar::from_text("S\na\nS->a\n").unwrap();
+        let single = Algo::LR1.build(&g);
+        assert!(!emit_rust(single.as_ref()).contains("Reduce"));
+        let all = tables().chain([("single".to_string(), Algo::LR1, single)]);
+        for (module, _, table) in all {
+            fs::write(
+                dir.join(format!("{}.rs", module)),
+                emit_rust(table.as_ref()),
+            )
+            .unwrap();
+            lib.push_str(&format!(
+                "pub mod {} {{\n    include!(\"{}.rs\");\n}}\n",
+                module, module
+            ));
+            for input in inputs(table.grammar()) {
+                stdin.push_str(&format!("{} {}\n", module, input));
+                expected.push_str(&format!("{}\n", interpreted(table.as_ref(), &input)));
+            }
+            modules.push(module);
+        }
+        lib.push_str(HARNESS);
+        lib.push_str(&format!("semantics!({});\n", modules.join(", ")));
+        fs::write(dir.join("generated.rs"), lib).unwrap();
+        fs::write(dir.join("main.rs"), MAIN).unwrap();
+
+        let rustc = |args: &[&str]| {
+            let out = Command::new("rustc")
+                .args(["--edition", "2021", "-D", "warnings", "--out-dir"])
+                .arg(&dir)
+                .args(args)
+                .current_dir(&dir)
+                .output()
+                .unwrap();
+            assert!(
+                out.status.success(),
+                "{}",
+                String::from_utf8_lossy(&out.stderr)
+            );
+        };
+        rustc(&["--crate-type", "rlib", "generated.rs"]);
+        rustc(&[
+            "--extern",
+            "generated=libgenerated.rlib",
+            "-L",
+            ".",
+            "main.rs",
+        ]);
+
+        check_output(Command::new(dir.join("main")), &stdin, &expected);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,393 @@
+use std::{collections::HashSet, fmt::Display};
+
+use crate::{
+    grammar::{Grammar, Sym},
+    stackautomata::StackAutomata,
+    table::lrtable::Table,
+};
+
+// (rule, place of dot, origin set)
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+struct Item {
+    rule: usize,
+    place: usize,
+    origin: usize,
+}
+
+/// Earley parser, accepts exactly the language of the grammar
+/// so it is used as reference for generated tables
+pub struct Earley<'a> {
+    gramm: &'a Grammar,
+    input: Vec<char>,
+    sets: Vec<Vec<Item>>,
+    contains: Vec<HashSet<Item>>,
+}
+
+impl<'a> Earley<'a> {
+    pub fn new(gramm: &'a Grammar, input: &str) -> Self {
+        let input: Vec<char> = input.chars().collect();
+        let mut res = Self {
+            gramm,
+            sets: vec![vec![]; input.len() + 1],
+            contains: vec![HashSet::new(); input.len() + 1],
+            input,
+        };
+        res.recognize();
+        res
+    }
+
+    fn add(&mut self, set: usize, item: Item) {
+        if self.contains[set].insert(item) {
+            self.sets[set].push(item);
+        }
+    }
+
+    fn next_sym(&self, item: &Item) -> Option<char> {
+        self.gramm.rules[item.rule].right.get(item.place).copied()
+    }
+
+    fn nullable(&self, sym: char) -> bool {
+        self.gramm.is_non_term(&sym) && self.gramm.first_from(&[sym]).contains(&Sym::Eps)
+    }
+
+    fn recognize(&mut self) {
+        self.add(
+            0,
+            Item {
+                rule: 0,
+                place: 0,
+                origin: 0,
+            },
+        );
+        for j in 0..self.sets.len() {
+            let mut k = 0;
+            while k < self.sets[j].len() {
+                let item = self.sets[j][k];
+                k += 1;
+                let next = Item {
+                    place: item.place + 1,
+                    ..item
+                };
+                match self.next_sym(&item) {
+                    Some(x) if self.gramm.is_non_term(&x) => {
+                        for r in self.gramm.rule_for_sym(x) {
+                            self.add(
+                                j,
+                                Item {
+                                    rule: r,
+                                    place: 0,
+                                    origin: j,
+                                },
+                            );
+                        }
+                        // completion of nullable symbol could be
+                        // done before this item was added
+                        if self.nullable(x) {
+                            self.add(j, next);
+                        }
+                    }
+                    Some(x) => {
+                        if self.input.get(j) == Some(&x) {
+                            self.add(j + 1, next);
+                        }
+                    }
+                    None => {
+                        let left = self.gramm.rules[item.rule].left;
+                        let mut i = 0;
+                        while i < self.sets[item.origin].len() {
+                            let waiting = self.sets[item.origin][i];
+                            i += 1;
+                            if self.next_sym(&waiting) == Some(left) {
+                                self.add(
+                                    j,
+                                    Item {
+                                        place: waiting.place + 1,
+                                        ..waiting
+                                    },
+                                );
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn completed(&self, sym: char, start: usize, end: usize) -> bool {
+        self.sets[end].iter().any(|x| {
+            x.origin == start && self.gramm.rules[x.rule].left == sym && self.next_sym(x).is_none()
+        })
+    }
+
+    pub fn accepts(&self) -> bool {
+        let n = self.input.len();
+        let len = self.gramm.rules[0].right.len();
+        self.contains[n].contains(&Item {
+            rule: 0,
+            place: len,
+            origin: 0,
+        })
+    }
+
+    /// Derivations as sequences of reduced rules (same as result of
+    /// StackAutomata), at most limit of them, cyclic derivations are skipped
+    pub fn derivations(&self, limit: usize) -> Vec<Vec<usize>> {
+        if !self.accepts() {
+            return vec![];
+        }
+        let mut open = HashSet::new();
+        self.derive_rule(0, 0, self.input.len(), limit, &mut open)
+    }
+
+    fn derive_sym(
+        &self,
+        sym: char,
+        start: usize,
+        end: usize,
+        limit: usize,
+        open: &mut HashSet<(char, usize, usize)>,
+    ) -> Vec<Vec<usize>> {
+        if !open.insert((sym, start, end)) {
+            return vec![];
+        }
+        let mut res = vec![];
+        for r in self.gramm.rule_for_sym(sym) {
+            if res.len() >= limit {
+                break;
+            }
+            let len = self.gramm.rules[r].right.len();
+            let done = Item {
+                rule: r,
+                place: len,
+                origin: start,
+            };
+            if self.contains[end].contains(&done) {
+                res.extend(self.derive_rule(r, start, end, limit - res.len(), open));
+            }
+        }
+        open.remove(&(sym, start, end));
+        res
+    }
+
+    fn derive_rule(
+        &self,
+        rule: usize,
+        start: usize,
+        end: usize,
+        limit: usize,
+        open: &mut HashSet<(char, usize, usize)>,
+    ) -> Vec<Vec<usize>> {
+        let len = self.gramm.rules[rule].right.len();
+        let mut res = self.derive_part(rule, len, start, end, limit, open);
+        res.iter_mut().for_each(|x| x.push(rule));
+        res
+    }
+
+    // derivations of right side of rule up to place over input[start..end]
+    fn derive_part(
+        &self,
+        rule: usize,
+        place: usize,
+        start: usize,
+        end: usize,
+        limit: usize,
+        open: &mut HashSet<(char, usize, usize)>,
+    ) -> Vec<Vec<usize>> {
+        if place == 0 {
+            return match start == end {
+                true => vec![vec![]],
+                false => vec![],
+            };
+        }
+        let before = Item {
+            rule,
+            place: place - 1,
+            origin: start,
+        };
+        let sym = self.gramm.rules[rule].right[place - 1];
+        let mut res = vec![];
+        if self.gramm.is_term(&sym) {
+            if end > start && self.input[end - 1] == sym && self.contains[end - 1].contains(&before)
+            {
+                res = self.derive_part(rule, place - 1, start, end - 1, limit, open);
+            }
+            return res;
+        }
+        for mid in start..=end {
+            if res.len() >= limit {
+                break;
+            }
+            if !self.contains[mid].contains(&before) || !self.completed(sym, mid, end) {
+                continue;
+            }
+            let prefixes = self.derive_part(rule, place - 1, start, mid, limit, open);
+            if prefixes.is_empty() {
+                continue;
+            }
+            let suffixes = self.derive_sym(sym, mid, end, limit, open);
+            for p in &prefixes {
+                for s in &suffixes {
+                    if res.len() >= limit {
+                        break;
+                    }
+                    let mut tmp = p.clone();
+                    tmp.extend(s);
+                    res.push(tmp);
+                }
+            }
+        }
+        res
+    }
+}
+
+/// Difference between LR table and the language of grammar
+#[derive(Debug, PartialEq, Eq)]
+pub enum Disagreement {
+    // input is in the language but table rejects it
+    Rejected {
+        derivations: Vec<Vec<usize>>,
+    },
+    // table accepts input that is not in the language
+    Accepted {
+        result: Vec<usize>,
+    },
+    // table accepts with derivation grammar does not have
+    Derivation {
+        result: Vec<usize>,
+        derivations: Vec<Vec<usize>>,
+    },
+}
+
+impl Display for Disagreement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let seq = |x: &Vec<usize>| {
+            x.iter()
+                .map(|r| r.to_string())
+                .collect::<Vec<String>>()
+                .join(" ")
+        };
+        match self {
+            Disagreement::Rejected { derivations } => {
+                write!(f, "table rejects input with derivation ")?;
+                write!(f, "{}", seq(&derivations[0]))
+            }
+            Disagreement::Accepted { result } => {
+                write!(f, "table accepts input outside of language ")?;
+                write!(f, "with result {}", seq(result))
+            }
+            Disagreement::Derivation {
+                result,
+                derivations,
+            } => {
+                write!(f, "table result {} is not a derivation, ", seq(result))?;
+                write!(f, "expected one of :")?;
+                for d in derivations {
+                    write!(f, " [{}]", seq(d))?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+const COMPARE_LIMIT: usize = 64;
+
+/// Runs table and Earley parser side by side, None when they agree
+pub fn compare(table: &dyn Table, input: &str) -> Option<Disagreement> {
+    let earley = Earley::new(table.grammar(), input);
+    let mut autom = StackAutomata::new(table, input);
+    let accepted = autom.run().is_ok() && autom.consumed();
+
+    match (accepted, earley.accepts()) {
+        (false, false) => None,
+        (false, true) => Some(Disagreement::Rejected {
+            derivations: earley.derivations(1),
+        }),
+        (true, false) => Some(Disagreement::Accepted {
+            result: autom.result().clone(),
+        }),
+        (true, true) => {
+            let derivations = earley.derivations(COMPARE_LIMIT);
+            if derivations.contains(autom.result()) || derivations.len() >= COMPARE_LIMIT {
+                return None;
+            }
+            Some(Disagreement::Derivation {
+                result: autom.result().clone(),
+                derivations,
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        graph::{
+            lr0node::LR0Node,
+            lr1graph::LR1Node,
+            lrgraph::{LR0Graph, LR1Graph, LRGraph},
+            lrnode::LRNode,
+        },
+        table::{lr0table::LR0Table, lr1table::LR1Table},
+    };
+
+    use super::*;
+
+    #[test]
+    fn recognize() {
+        let g = Grammar::from_text(include_str!("../examples/example.gram")).unwrap();
+        assert!(Earley::new(&g, "a+a*(a+a)").accepts());
+        assert!(Earley::new(&g, "((a))").accepts());
+        assert!(!Earley::new(&g, "a+").accepts());
+        assert!(!Earley::new(&g, "").accepts());
+        assert_eq!(
+            Earley::new(&g, "a*a").derivations(10),
+            vec![vec![5, 4, 5, 3, 2, 0]]
+        );
+    }
+
+    #[test]
+    fn epsilon() {
+        let g = Grammar::from_text(include_str!("../examples/simple.gram")).unwrap();
+        assert_eq!(Earley::new(&g, "").derivations(10), vec![vec![2, 0]]);
+        assert_eq!(
+            Earley::new(&g, "aabb").derivations(10),
+            vec![vec![2, 1, 1, 0]]
+        );
+        assert!(!Earley::new(&g, "aab").accepts());
+
+        let g = Grammar::from_text("S A B\na\nS->AB\nA->a\nA->\nB->a\nB->\n").unwrap();
+        let mut d = Earley::new(&g, "a").derivations(10);
+        d.sort();
+        assert_eq!(d, vec![vec![1, 4, 0], vec![2, 3, 0]]);
+    }
+
+    #[test]
+    fn ambiguous() {
+        let g = Grammar::from_text("S E\na +\nS->E\nE->E+E\nE->a\n").unwrap();
+        assert_eq!(Earley::new(&g, "a+a+a+a").derivations(100).len(), 5);
+        assert_eq!(Earley::new(&g, "a+a+a+a").derivations(3).len(), 3);
+
+        let g = Grammar::from_text("S A\na\nS->A\nA->A\nA->a\n").unwrap();
+        assert_eq!(Earley::new(&g, "a").derivations(10), vec![vec![2, 0]]);
+    }
+
+    #[test]
+    fn against_tables() {
+        let g = Grammar::from_text(include_str!("../examples/assign.gram")).unwrap();
+        let mut graph = LR1Graph::new();
+        graph.construct(LR1Node::default(&g));
+        let lr1 = LR1Table::new(graph, &g);
+        for input in ["a=a", "*a=**a", "**a", "a=", "=a", "a=a=a"] {
+            assert_eq!(compare(&lr1, input), None);
+        }
+
+        let mut graph = LR0Graph::new();
+        graph.construct(LR0Node::default(&g));
+        let lr0 = LR0Table::new(graph, &g);
+        assert!(matches!(
+            compare(&lr0, "a=a"),
+            Some(Disagreement::Rejected { .. })
+        ));
+    }
+}
@@ -0,0 +1,219 @@
+pub(crate) mod sppf;
+mod stackgraph;
+
+use crate::{
+    grammar::{Grammar, Sym},
+    table::lrtable::{Action, Table},
+};
+
+use self::{
+    sppf::{Forest, PackedNode},
+    stackgraph::StackGraph,
+};
+
+/// Generalized LR (Tomita) parser, instead of failing on
+/// conflicting cell it follows every action of the cell
+/// and returns all parses as shared packed parse forest
+pub struct GLRParser<'a> {
+    table: &'a dyn Table,
+    gramm: &'a Grammar,
+}
+
+impl<'a> GLRParser<'a> {
+    pub fn new(table: &'a dyn Table) -> Self {
+        Self {
+            table,
+            gramm: table.grammar(),
+        }
+    }
+
+    /// On error returns position in input where all stacks died
+    pub fn parse(&self, input: &str) -> Result<Forest, usize> {
+        let input: Vec<char> = input.chars().collect();
+        let mut gss = StackGraph::new();
+        let mut forest = Forest::new();
+
+        for i in 0..=input.len() {
+            let lookahead = match input.get(i) {
+                Some(c) => Sym::Normal(*c),
+                None => Sym::Eps,
+            };
+            self.reduce_all(&mut gss, &mut forest, i, lookahead, i == input.len());
+            if i == input.len() {
+                break;
+            }
+
+            let c = input[i];
+            let term = forest.node(c, i, i + 1, true);
+            for v in gss.level(i).to_vec() {
+                let state = gss.node(v).state;
+                if !self
+                    .table
+                    .get_actions(state, lookahead)
+                    .contains(&Action::Shift)
+                {
+                    continue;
+                }
+                if let Some(target) = self.table.get_goto(state, c) {
+                    let w = match gss.find(i + 1, target) {
+                        Some(w) => w,
+                        None => gss.add_node(i + 1, target),
+                    };
+                    gss.add_edge(w, v, term);
+                }
+            }
+            if gss.level(i + 1).is_empty() {
+                return Err(i);
+            }
+        }
+
+        match forest.root() {
+            Some(_) => Ok(forest),
+            None => Err(input.len()),
+        }
+    }
+
+    // does reductions on one level until nothing new is added,
+    // new edge can make new paths for already done reductions
+    fn reduce_all(
+        &self,
+        gss: &mut StackGraph,
+        forest: &mut Forest,
+        level: usize,
+        lookahead: Sym,
+        end: bool,
+    ) {
+        let mut changed = true;
+        while changed {
+            changed = false;
+            let mut k = 0;
+            while k < gss.level(level).len() {
+                let v = gss.level(level)[k];
+                k += 1;
+                for a in self.table.get_actions(gss.node(v).state, lookahead) {
+                    changed |= match a {
+                        Action::Reduction(r) => self.reduce(gss, forest, v, r, end),
+                        Action::Accept => self.reduce(gss, forest, v, 0, end),
+                        _ => false,
+                    };
+                }
+            }
+        }
+    }
+
+    fn reduce(
+        &self,
+        gss: &mut StackGraph,
+        forest: &mut Forest,
+        v: usize,
+        rule: usize,
+        end: bool,
+    ) -> bool {
+        let r = &self.gramm.rules[rule];
+        let level = gss.node(v).level;
+        let mut changed = false;
+        for (u, children) in gss.paths(v, r.right.len()) {
+            let start = gss.node(u).level;
+            let node = forest.node(r.left, start, level, false);
+            changed |= forest.add_packed(node, PackedNode { rule, children });
+            if rule == 0 && u == 0 && end {
+                forest.set_root(node);
+            }
+            let target = match self.table.get_goto(gss.node(u).state, r.left) {
+                Some(t) => t,
+                None => continue,
+            };
+            let w = match gss.find(level, target) {
+                Some(w) => w,
+                None => {
+                    changed = true;
+                    gss.add_node(level, target)
+                }
+            };
+            changed |= gss.add_edge(w, u, node);
+        }
+        changed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        graph::{
+            lalrgraph::LALRGraph,
+            lalrnode::LALRNode,
+            lr0node::LR0Node,
+            lr1graph::LR1Node,
+            lrgraph::{LR0Graph, LR1Graph, LRGraph},
+            lrnode::LRNode,
+        },
+        table::{lr0table::LR0Table, lr1table::LR1Table, slr1table::SLR1Table},
+    };
+
+    use super::*;
+
+    fn tables(g: &Grammar) -> Vec<Box<dyn Table + '_>> {
+        let mut lr0 = LR0Graph::new();
+        lr0.construct(LR0Node::default(g));
+        let mut slr = LR0Graph::new();
+        slr.construct(LR0Node::default(g));
+        let mut lr1 = LR1Graph::new();
+        lr1.construct(LR1Node::default(g));
+        let mut lalr = LALRGraph::new();
+        lalr.construct(LALRNode::default(g));
+        vec![
+            Box::new(LR0Table::new(lr0, g)),
+            Box::new(SLR1Table::new(slr, g)),
+            Box::new(LR1Table::new(lr1, g)),
+            Box::new(LR1Table::new(lalr, g)),
+        ]
+    }
+
+    fn count(g: &Grammar, input: &str) -> Vec<Option<u128>> {
+        tables(g)
+            .iter()
+            .map(|t| match GLRParser::new(t.as_ref()).parse(input) {
+                Ok(f) => f.count_derivations(),
+                Err(_) => Some(0),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn ambiguous() {
+        let g = Grammar::from_text("S E\na +\nS->E\nE->E+E\nE->a\n").unwrap();
+        assert_eq!(count(&g, "a"), vec![Some(1); 4]);
+        assert_eq!(count(&g, "a+a+a"), vec![Some(2); 4]);
+        assert_eq!(count(&g, "a+a+a+a"), vec![Some(5); 4]);
+        assert_eq!(count(&g, "a+a+"), vec![Some(0); 4]);
+    }
+
+    #[test]
+    fn epsilon() {
+        let g = Grammar::from_text(include_str!("../../examples/simple.gram")).unwrap();
+        assert_eq!(count(&g, ""), vec![Some(1); 4]);
+        assert_eq!(count(&g, "aaabbb"), vec![Some(1); 4]);
+        assert_eq!(count(&g, "aabbb"), vec![Some(0); 4]);
+
+        let g = Grammar::from_text("S A B\na\nS->AB\nA->a\nA->\nB->a\nB->\n").unwrap();
+        assert_eq!(count(&g, ""), vec![Some(1); 4]);
+        assert_eq!(count(&g, "a"), vec![Some(2); 4]);
+        assert_eq!(count(&g, "aa"), vec![Some(1); 4]);
+    }
+
+    #[test]
+    fn cyclic() {
+        let g = Grammar::from_text("S A\na\nS->A\nA->A\nA->a\n").unwrap();
+        assert_eq!(count(&g, "a"), vec![None; 4]);
+    }
+
+    #[test]
+    fn deterministic() {
+        let g = Grammar::from_text(include_str!("../../examples/assign.gram")).unwrap();
+        let t = tables(&g);
+        let forest = GLRParser::new(t[2].as_ref()).parse("*a=**a").unwrap();
+        assert_eq!(forest.count_derivations(), Some(1));
+        assert_eq!(GLRParser::new(t[2].as_ref()).parse("a=*").err(), Some(3));
+        assert_eq!(GLRParser::new(t[2].as_ref()).parse("a=a=").err(), Some(3));
+    }
+}
@@ -0,0 +1,155 @@
+use std::{collections::HashMap, fmt::Display};
+
+/// One way how to derive symbol node, children are
+/// indices of symbol nodes in order of the rule
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackedNode {
+    pub rule: usize,
+    pub children: Vec<usize>,
+}
+
+/// Symbol deriving input[start..end], terminals have no packed nodes
+#[derive(Debug)]
+pub struct SymbolNode {
+    pub sym: char,
+    pub start: usize,
+    pub end: usize,
+    pub terminal: bool,
+    pub packed: Vec<PackedNode>,
+}
+
+impl Display for SymbolNode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}[{},{}]", self.sym, self.start, self.end)
+    }
+}
+
+/// Shared packed parse forest
+#[derive(Debug)]
+pub struct Forest {
+    nodes: Vec<SymbolNode>,
+    index: HashMap<(char, usize, usize), usize>,
+    root: Option<usize>,
+}
+
+#[derive(Clone, Copy)]
+enum Visit {
+    Open,
+    Done(u128),
+}
+
+impl Display for Forest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.count_derivations() {
+            Some(x) => writeln!(f, "derivations : {}", x)?,
+            None => writeln!(f, "derivations : infinite")?,
+        }
+        let root = match self.root {
+            Some(r) => r,
+            None => return Ok(()),
+        };
+        let mut visited = vec![false; self.nodes.len()];
+        let mut stack = vec![root];
+        visited[root] = true;
+        while let Some(n) = stack.pop() {
+            let node = &self.nodes[n];
+            for p in &node.packed {
+                write!(f, "{} -> ({})", node, p.rule)?;
+                if p.children.is_empty() {
+                    write!(f, " eps")?;
+                }
+                for c in &p.children {
+                    write!(f, " {}", self.nodes[*c])?;
+                }
+                writeln!(f)?;
+            }
+            for p in node.packed.iter().rev() {
+                for c in p.children.iter().rev() {
+                    if !visited[*c] && !self.nodes[*c].terminal {
+                        visited[*c] = true;
+                        stack.push(*c);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Forest {
+    pub(crate) fn new() -> Self {
+        Self {
+            nodes: vec![],
+            index: HashMap::new(),
+            root: None,
+        }
+    }
+
+    pub fn root(&self) -> Option<usize> {
+        self.root
+    }
+
+    pub fn symbol(&self, node: usize) -> &SymbolNode {
+        &self.nodes[node]
+    }
+
+    pub(crate) fn set_root(&mut self, root: usize) {
+        self.root = Some(root);
+    }
+
+    // finds or creates node for symbol over input[start..end]
+    pub(crate) fn node(&mut self, sym: char, start: usize, end: usize, terminal: bool) -> usize {
+        if let Some(i) = self.index.get(&(sym, start, end)) {
+            return *i;
+        }
+        self.nodes.push(SymbolNode {
+            sym,
+            start,
+            end,
+            terminal,
+            packed: vec![],
+        });
+        let i = self.nodes.len() - 1;
+        self.index.insert((sym, start, end), i);
+        i
+    }
+
+    // returns true when this derivation was not known before
+    pub(crate) fn add_packed(&mut self, node: usize, packed: PackedNode) -> bool {
+        if self.nodes[node].packed.contains(&packed) {
+            return false;
+        }
+        self.nodes[node].packed.push(packed);
+        true
+    }
+
+    /// Number of distinct derivations of the root,
+    /// None when the forest is cyclic (infinitely many)
+    pub fn count_derivations(&self) -> Option<u128> {
+        let root = self.root?;
+        let mut visits: Vec<Option<Visit>> = vec![None; self.nodes.len()];
+        self.count_from(root, &mut visits)
+    }
+
+    fn count_from(&self, node: usize, visits: &mut Vec<Option<Visit>>) -> Option<u128> {
+        match visits[node] {
+            Some(Visit::Open) => return None,
+            Some(Visit::Done(x)) => return Some(x),
+            None => (),
+        }
+        if self.nodes[node].terminal {
+            return Some(1);
+        }
+        visits[node] = Some(Visit::Open);
+        let mut res: u128 = 0;
+        for p in &self.nodes[node].packed {
+            let mut tmp: u128 = 1;
+            for c in &p.children {
+                tmp = tmp.saturating_mul(self.count_from(*c, visits)?);
+            }
+            res = res.saturating_add(tmp);
+        }
+        visits[node] = Some(Visit::Done(res));
+        Some(res)
+    }
+}
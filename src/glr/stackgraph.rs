@@ -0,0 +1,87 @@
+// Graph structured stack, every node is one state on some level
+// (input position), edges point back to previous node on the stack
+// and carry the forest node of the symbol between them
+#[derive(Debug)]
+pub(crate) struct StackNode {
+    pub(crate) state: usize,
+    pub(crate) level: usize,
+    // (previous node, forest node)
+    pub(crate) edges: Vec<(usize, usize)>,
+}
+
+#[derive(Debug)]
+pub(crate) struct StackGraph {
+    nodes: Vec<StackNode>,
+    levels: Vec<Vec<usize>>,
+}
+
+impl StackGraph {
+    pub(crate) fn new() -> Self {
+        let mut res = Self {
+            nodes: vec![],
+            levels: vec![],
+        };
+        res.add_node(0, 0);
+        res
+    }
+
+    pub(crate) fn node(&self, index: usize) -> &StackNode {
+        &self.nodes[index]
+    }
+
+    pub(crate) fn level(&self, level: usize) -> &[usize] {
+        match self.levels.get(level) {
+            Some(l) => l,
+            None => &[],
+        }
+    }
+
+    pub(crate) fn find(&self, level: usize, state: usize) -> Option<usize> {
+        self.level(level)
+            .iter()
+            .find(|x| self.nodes[**x].state == state)
+            .copied()
+    }
+
+    pub(crate) fn add_node(&mut self, level: usize, state: usize) -> usize {
+        self.nodes.push(StackNode {
+            state,
+            level,
+            edges: vec![],
+        });
+        if self.levels.len() <= level {
+            self.levels.resize(level + 1, vec![]);
+        }
+        let index = self.nodes.len() - 1;
+        self.levels[level].push(index);
+        index
+    }
+
+    // returns true when edge was not there before
+    pub(crate) fn add_edge(&mut self, from: usize, to: usize, label: usize) -> bool {
+        if self.nodes[from].edges.iter().any(|(t, _)| *t == to) {
+            return false;
+        }
+        self.nodes[from].edges.push((to, label));
+        true
+    }
+
+    // all paths of given length going back from node,
+    // labels are returned in order of the input
+    pub(crate) fn paths(&self, from: usize, len: usize) -> Vec<(usize, Vec<usize>)> {
+        let mut res = vec![(from, vec![])];
+        for _ in 0..len {
+            let mut next = vec![];
+            for (node, labels) in res {
+                for (to, label) in &self.nodes[node].edges {
+                    let mut tmp: Vec<usize> = labels.clone();
+                    tmp.push(*label);
+                    next.push((*to, tmp));
+                }
+            }
+            res = next;
+        }
+        res.iter_mut().for_each(|(_, labels)| labels.reverse());
+        res
+    }
+}
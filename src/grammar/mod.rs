@@ -4,6 +4,10 @@ use std::{
     fmt::Display,
 };
 
+/// Reserved terminal for error recovery, written as `error`
+/// in right side of rules, it is added to terminals when used
+pub const ERROR: char = '\u{E000}';
+
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
 pub enum Sym {
     Normal(char),
@@ -13,13 +17,14 @@ pub enum Sym {
 impl Display for Sym {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
+            Sym::Normal(ERROR) => write!(f, "error"),
             Sym::Normal(x) => write!(f, "{}", x),
             Sym::Eps => write!(f, "eps"),
         }
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Grammar {
     pub(crate) non_terms: HashSet<char>,
     pub(crate) terms: HashSet<char>,
@@ -37,7 +42,9 @@ impl Display for Grammar {
 }
 
 impl Grammar {
-    pub(crate) fn new(non_terms: HashSet<char>, terms: HashSet<char>) -> Self {
+    /// Grammar without rules, add them with [`Grammar::add_rule`] and call
+    /// [`Grammar::update_sets`] before building a table
+    pub fn new(non_terms: HashSet<char>, terms: HashSet<char>) -> Self {
         Self {
             non_terms,
             terms,
@@ -47,22 +54,147 @@ impl Grammar {
         }
     }
 
-    pub fn add_rule(&mut self, left: char, right: &str) -> Result<(), &'static str> {
-        if !self.non_terms.contains(&left) {
-            return Err("left must be non terminal");
+    /// Loads grammar from the `.gram` format: first line non-terminals,
+    /// second line terminals (both space separated), then one `A->xyz`
+    /// rule per line. First rule is the starting one. Word `error`
+    /// in right side stands for the reserved error terminal, see
+    /// [`Grammar::add_rule_text`].
+    pub fn from_text(text: &str) -> Result<Self, &'static str> {
+        let lines: Vec<&str> = text.split('\n').collect();
+        if lines.len() < 2 {
+            return Err("Wrong format : atleast non-terminals and terminals needed");
         }
 
-        if right
-            .chars()
-            .any(|x| !self.is_term(&x) && !self.is_non_term(&x))
-        {
-            return Err("right must contain only terminals and non terminals");
+        let non_terms = HashSet::from_iter(Self::get_chars(lines[0])?);
+        let terms = HashSet::from_iter(Self::get_chars(lines[1])?);
+
+        let mut res = Grammar::new(non_terms, terms);
+        for line in &lines[2..] {
+            if line.is_empty() {
+                continue;
+            }
+            res.add_rule_text(line)?;
+        }
+        if res.rules.is_empty() {
+            return Err("Grammar must contain atleast one rule");
         }
+        res.create_first();
+        res.create_follow();
+        Ok(res)
+    }
 
-        let rule = Rule::new(left, right.chars().collect());
+    /// One rule in `A->xyz` format, FIRST and FOLLOW are not updated.
+    /// `error` is the reserved terminal only as a whole word, not next to
+    /// letters or digits, spaces around it are separators (`A->x error;`).
+    /// It is rejected when `e`, `r` and `o` are all symbols of the grammar,
+    /// [`ERROR`] itself can be written instead.
+    pub fn add_rule_text(&mut self, line: &str) -> Result<(), &'static str> {
+        let sides: Vec<&str> = line.split("->").collect();
+        if sides.len() != 2 {
+            return Err("Wrong format of rule");
+        }
+        if sides[0].chars().count() != 1 {
+            return Err("Wrong format of rule");
+        }
+        let right = self.error_words(sides[1])?;
+        self.add_rule_vec(sides[0].chars().next().unwrap(), right)
+    }
 
-        self.rules.push(rule);
-        Ok(())
+    // right side with `error` words replaced by ERROR
+    fn error_words(&self, right: &str) -> Result<Vec<char>, &'static str> {
+        let chars: Vec<char> = right.chars().collect();
+        let word: Vec<char> = "error".chars().collect();
+        let apart = |x: Option<&char>| x.is_none_or(|x| !x.is_alphanumeric());
+        let mut res = vec![];
+        let mut i = 0;
+        while i < chars.len() {
+            let end = i + word.len();
+            let found = chars[i..].starts_with(&word)
+                && apart(i.checked_sub(1).and_then(|x| chars.get(x)))
+                && apart(chars.get(end));
+            if !found {
+                res.push(chars[i]);
+                i += 1;
+                continue;
+            }
+            if word.iter().all(|x| self.is_term(x) || self.is_non_term(x)) {
+                return Err("error is ambiguous, e r o are symbols of grammar");
+            }
+            if res.last() == Some(&' ') {
+                res.pop();
+            }
+            res.push(ERROR);
+            i = end;
+            if chars.get(i) == Some(&' ') {
+                i += 1;
+            }
+        }
+        Ok(res)
+    }
+
+    /// Starting rule 0 can not be removed, FIRST and FOLLOW are not updated
+    pub fn remove_rule(&mut self, rule: usize) -> Result<Rule, &'static str> {
+        if rule >= self.rules.len() {
+            return Err("No such rule");
+        }
+        // any other rule would become the starting one
+        if rule == 0 {
+            return Err("Starting rule can not be removed");
+        }
+        Ok(self.rules.remove(rule))
+    }
+
+    /// FIRST and FOLLOW again after rules were changed
+    pub fn update_sets(&mut self) {
+        self.first.clear();
+        self.create_first();
+        self.create_follow();
+    }
+
+    pub fn terms(&self) -> &HashSet<char> {
+        &self.terms
+    }
+
+    pub fn non_terms(&self) -> &HashSet<char> {
+        &self.non_terms
+    }
+
+    /// Rules in order, rule 0 is the starting one
+    pub fn rules(&self) -> &[Rule] {
+        &self.rules
+    }
+
+    /// FNV-1a hash of symbols and rules, same for equal grammars
+    /// regardless of order of symbols in the input
+    pub fn fingerprint(&self) -> u64 {
+        let mut non_terms: Vec<char> = self.non_terms.iter().copied().collect();
+        non_terms.sort();
+        let mut terms: Vec<char> = self.terms.iter().copied().collect();
+        terms.sort();
+        let mut text: String = non_terms.into_iter().collect();
+        text.push('\n');
+        text.extend(terms);
+        for r in &self.rules {
+            text.push('\n');
+            text.push(r.left);
+            text.extend(&r.right);
+        }
+        text.bytes().fold(0xcbf29ce484222325, |hash, b| {
+            (hash ^ b as u64).wrapping_mul(0x100000001b3)
+        })
+    }
+
+    fn get_chars(line: &str) -> Result<Vec<char>, &'static str> {
+        let chars = line.split(' ').collect::<Vec<&str>>();
+
+        if chars.iter().any(|x| x.chars().count() != 1) {
+            return Err("Non terminals must be one character");
+        }
+        Ok(chars.iter().map(|x| x.chars().next().unwrap()).collect())
+    }
+
+    pub fn add_rule(&mut self, left: char, right: &str) -> Result<(), &'static str> {
+        self.add_rule_vec(left, right.chars().collect())
     }
 
     pub(crate) fn add_rule_vec(
@@ -76,26 +208,30 @@ impl Grammar {
 
         if right
             .iter()
-            .any(|x| !self.non_terms.contains(&x) && !self.terms.contains(&x))
+            .any(|x| *x != ERROR && !self.non_terms.contains(x) && !self.terms.contains(x))
         {
             return Err("right must contain only terminals and non terminals");
         }
 
+        if right.contains(&ERROR) {
+            self.terms.insert(ERROR);
+        }
+
         let rule = Rule::new(left, right);
 
         self.rules.push(rule);
         Ok(())
     }
 
-    pub(crate) fn is_term(&self, sym: &char) -> bool {
+    pub fn is_term(&self, sym: &char) -> bool {
         self.terms.contains(sym)
     }
 
-    pub(crate) fn is_non_term(&self, sym: &char) -> bool {
+    pub fn is_non_term(&self, sym: &char) -> bool {
         self.non_terms.contains(sym)
     }
 
-    pub(crate) fn rule_for_sym(&self, sym: char) -> Vec<usize> {
+    pub fn rule_for_sym(&self, sym: char) -> Vec<usize> {
         let mut res: Vec<usize> = vec![];
         for i in 0..(self.rules.len()) {
             if self.rules[i].left == sym {
@@ -125,7 +261,7 @@ impl Grammar {
 
     pub fn first_from(&self, syms: &[char]) -> HashSet<Sym> {
         let mut res: HashSet<Sym> = HashSet::new();
-        if syms.len() == 0 {
+        if syms.is_empty() {
             res.insert(Sym::Eps);
         } else if self.is_term(&syms[0]) {
             res.insert(Sym::Normal(syms[0]));
@@ -144,7 +280,7 @@ impl Grammar {
         res
     }
 
-    pub(crate) fn follow(&self, non_term: char) -> &HashSet<Sym> {
+    pub fn follow(&self, non_term: char) -> &HashSet<Sym> {
         self.follow.get(&non_term).unwrap()
     }
 
@@ -163,7 +299,7 @@ impl Grammar {
                     to_add = HashSet::from_iter(to_add.union(self.follow(rule.left)).copied());
                 }
                 let new: HashSet<Sym> = HashSet::from_iter(orig.union(&to_add).copied());
-                added = new.len() != orig_len;
+                added |= new.len() != orig_len;
                 self.follow.insert(nterm, new);
             }
         }
@@ -187,7 +323,7 @@ impl Grammar {
 }
 
 #[derive(Debug, Clone)]
-pub(crate) struct Rule {
+pub struct Rule {
     pub(crate) left: char,
     pub(crate) right: Vec<char>,
 }
@@ -196,6 +332,27 @@ impl Rule {
     fn new(left: char, right: Vec<char>) -> Self {
         Self { left, right }
     }
+
+    pub fn left(&self) -> char {
+        self.left
+    }
+
+    pub fn right(&self) -> &[char] {
+        &self.right
+    }
+}
+
+impl Display for Rule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ->", self.left)?;
+        if self.right.is_empty() {
+            write!(f, " {}", Sym::Eps)?;
+        }
+        for c in &self.right {
+            write!(f, " {}", Sym::Normal(*c))?;
+        }
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -215,6 +372,78 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn error_terminal() {
+        let g = Grammar::from_text("S A\na ;\nS->A\nA->a;\nA->error;\n").unwrap();
+        assert!(g.is_term(&ERROR));
+        assert_eq!(g.rules[2].right, vec![ERROR, ';']);
+        assert_eq!(Sym::Normal(ERROR).to_string(), "error");
+        assert!(g.follow('A').contains(&Sym::Eps));
+
+        let mut g = Grammar::from_text("S A\na ;\nS->A\nA->a\n").unwrap();
+        g.add_rule_text("A->a error ;").unwrap();
+        assert_eq!(g.rules[2].right, vec!['a', ERROR, ';']);
+
+        // with e r o as symbols only part of a longer word is allowed
+        let mut g = Grammar::from_text("S A\na ; e r o t\nS->A\nA->a\n").unwrap();
+        g.add_rule_text("A->terror").unwrap();
+        assert_eq!(g.rules[2].right, "terror".chars().collect::<Vec<_>>());
+        assert!(g.add_rule_text("A->error").is_err());
+        g.add_rule_text(&format!("A->{};", ERROR)).unwrap();
+        assert_eq!(g.rules[3].right, vec![ERROR, ';']);
+
+        // invalid rule does not add the error terminal
+        let mut g = Grammar::from_text("S A\na\nS->A\nA->a\n").unwrap();
+        assert!(g.add_rule_text("A->error b").is_err());
+        assert!(!g.is_term(&ERROR));
+    }
+
+    #[test]
+    fn follow_fixpoint() {
+        // w reaches FOLLOW(A) in a pass where only A changes, it is not
+        // the last non-terminal of S->AC and B gets it in the next pass
+        let g = Grammar::from_text(
+            "Z V A S T U C B\nw c b\nZ->S\nV->Cw\nA->B\nS->AC\nT->S\nU->Tw\nC->c\nC->\nB->b\n",
+        )
+        .unwrap();
+        let all = HashSet::from([Sym::Normal('c'), Sym::Normal('w'), Sym::Eps]);
+        assert_eq!(g.follow('A'), &all);
+        assert_eq!(g.follow('B'), &all);
+    }
+
+    #[test]
+    fn fingerprint() {
+        let a = Grammar::from_text("S A\na b\nS->A\nA->a\n").unwrap();
+        let b = Grammar::from_text("A S\nb a\nS->A\nA->a\n").unwrap();
+        let c = Grammar::from_text("S A\na b\nS->A\nA->b\n").unwrap();
+        assert_eq!(a.fingerprint(), b.fingerprint());
+        assert_ne!(a.fingerprint(), c.fingerprint());
+    }
+
+    #[test]
+    fn edit_rules() {
+        let mut g = Grammar::from_text("S A\na b\nS->A\nA->a\n").unwrap();
+        assert!(g.add_rule_text("A->b").is_ok());
+        assert!(g.add_rule_text("A=>b").is_err());
+        assert!(g.add_rule_text("A->c").is_err());
+        g.update_sets();
+        assert_eq!(
+            g.first_from(&['A']),
+            HashSet::from([Sym::Normal('a'), Sym::Normal('b')])
+        );
+
+        assert_eq!(g.remove_rule(1).unwrap().to_string(), "A -> a");
+        assert!(g.remove_rule(5).is_err());
+        assert_eq!(
+            g.remove_rule(0).err(),
+            Some("Starting rule can not be removed")
+        );
+        g.add_rule_text("A->").unwrap();
+        g.update_sets();
+        assert!(g.first_from(&['S']).contains(&Sym::Eps));
+        assert!(!g.first_from(&['S']).contains(&Sym::Normal('a')));
+    }
+
     #[test]
     fn basic() {
         let mut g = dummy_gramm();
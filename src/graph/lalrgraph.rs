@@ -29,7 +29,7 @@ impl<'a> LALRGraph<'a> {
         let g = self.lr1_graph.nodes()[index].gramm();
 
         for (c, rules) in steps {
-            let nnode = LALRNode::new(HashSet::from_iter(rules.into_iter()), c, g);
+            let nnode = LALRNode::new(HashSet::from_iter(rules), c, g);
             let (e, i) = self.exist(&nnode);
             if e {
                 self.lr1_graph.insert_edge(index, c, i);
@@ -64,7 +64,7 @@ impl<'a> LRGraph<'a, LALRNode<'a>, LR1Rule> for LALRGraph<'a> {
         let g = self.lr1_graph.nodes()[index].gramm();
 
         for (c, rules) in steps {
-            let nnode = LALRNode::new(HashSet::from_iter(rules.into_iter()), c, g);
+            let nnode = LALRNode::new(HashSet::from_iter(rules), c, g);
             let (e, i) = self.exist(&nnode);
             if e {
                 self.lr1_graph.insert_edge(index, c, i);
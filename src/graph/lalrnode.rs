@@ -1,4 +1,4 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashSet};
 
 use crate::grammar::Grammar;
 
@@ -41,7 +41,7 @@ impl<'a> LRNode<'a, LR1Rule> for LALRNode<'a> {
         self.lr1node.create_closure()
     }
 
-    fn get_steps(&self) -> HashMap<char, Vec<LR1Rule>> {
+    fn get_steps(&self) -> BTreeMap<char, Vec<LR1Rule>> {
         self.lr1node.get_steps()
     }
 
@@ -13,7 +13,7 @@ mod tests {
     use super::*;
 
     fn test_closure(rules: Vec<LR0Rule>, closure: Vec<LR0Rule>, gramm: &Grammar) {
-        let mut lr0node = LR0Node::new(HashSet::from_iter(rules.into_iter()), 'X', &gramm);
+        let mut lr0node = LR0Node::new(HashSet::from_iter(rules), 'X', gramm);
 
         lr0node.create_closure();
         println!("{:?}", lr0node.closure());
@@ -101,11 +101,11 @@ mod tests {
     }
 
     fn test_steps(base: Vec<LR0Rule>, syms: Vec<char>, gramm: &Grammar) {
-        let mut lr0node = LR0Node::new(HashSet::from_iter(base.into_iter()), 'X', gramm);
+        let mut lr0node = LR0Node::new(HashSet::from_iter(base), 'X', gramm);
         lr0node.create_closure();
         let gen_syms = lr0node.get_steps();
 
-        let hset: HashSet<char> = HashSet::from_iter(syms.into_iter());
+        let hset: HashSet<char> = HashSet::from_iter(syms);
 
         assert_eq!(gen_syms.len(), hset.len());
         //assert_eq!(HashSet::from_iter(syms.into_iter()), gen_syms);
@@ -1,6 +1,6 @@
 use std::collections::HashSet;
 
-use crate::Grammar;
+use crate::{grammar::Sym, Grammar};
 
 use super::rule::LRRule;
 
@@ -56,6 +56,25 @@ impl LRRule for LR0Rule {
         }
         res
     }
+
+    fn describe(&self, gramm: &Grammar) -> String {
+        let rule = &gramm.rules[self.rule];
+        let mut res = format!("{} ->", rule.left);
+        for (i, c) in rule.right.iter().enumerate() {
+            if i == self.place {
+                res.push_str(" .");
+            }
+            res.push_str(&format!(" {}", Sym::Normal(*c)));
+        }
+        if self.place >= rule.right.len() {
+            res.push_str(" .");
+        }
+        res
+    }
+
+    fn parts(&self) -> (LR0Rule, Option<Sym>) {
+        (*self, None)
+    }
 }
 
 #[cfg(test)]
@@ -87,4 +106,13 @@ mod tests {
         assert_eq!(LR0Rule::new(1, 0).get_sym(&g), Some('a'));
         assert_eq!(LR0Rule::new(1, 1).get_sym(&g), None);
     }
+
+    #[test]
+    fn test_describe() {
+        let g = dummy_gramm();
+
+        assert_eq!(LR0Rule::new(0, 0).describe(&g), "S -> . a A");
+        assert_eq!(LR0Rule::new(0, 1).describe(&g), "S -> a . A");
+        assert_eq!(LR0Rule::new(1, 1).describe(&g), "A -> a .");
+    }
 }
@@ -34,7 +34,7 @@ impl LR1Rule {
     }
 
     fn get_new_follow(&self, gramm: &Grammar) -> HashSet<Sym> {
-        if let Some(rest) = self.get_rest(&gramm) {
+        if let Some(rest) = self.get_rest(gramm) {
             match self.follow {
                 Sym::Normal(x) => {
                     let mut tmp = Vec::from(rest);
@@ -67,7 +67,7 @@ impl LRRule for LR1Rule {
     }
 
     fn next_rule(&self) -> LR1Rule {
-        LR1Rule::new(self.lr0.rule, self.lr0.place + 1, self.follow.clone())
+        LR1Rule::new(self.lr0.rule, self.lr0.place + 1, self.follow)
     }
 
     fn create_closure(&self, gramm: &Grammar) -> HashSet<Self> {
@@ -84,6 +84,14 @@ impl LRRule for LR1Rule {
         }
         res
     }
+
+    fn describe(&self, g: &Grammar) -> String {
+        format!("[{}, {}]", self.lr0.describe(g), self.follow)
+    }
+
+    fn parts(&self) -> (LR0Rule, Option<Sym>) {
+        (self.lr0, Some(self.follow))
+    }
 }
 
 pub type LR1Node<'a> = LRNodeStruct<'a, LR1Rule>;
@@ -3,6 +3,8 @@ use std::{
     marker::PhantomData,
 };
 
+use crate::grammar::Sym;
+
 use super::{
     lr0node::LR0Node,
     lr0rule::LR0Rule,
@@ -107,7 +109,7 @@ where
         let g = self.nodes[index].gramm();
 
         for (c, rules) in steps {
-            let nnode = T::new(HashSet::from_iter(rules.into_iter()), c, g);
+            let nnode = T::new(HashSet::from_iter(rules), c, g);
             let (e, i) = self.exist(&nnode);
             if e {
                 self.edges[index].insert(c, i);
@@ -127,3 +129,85 @@ where
         &self.edges
     }
 }
+
+// Item of state for reports
+#[derive(Debug, Clone)]
+pub(crate) struct Item {
+    pub(crate) rule: usize,
+    pub(crate) dot: usize,
+    // None for LR(0) item
+    pub(crate) lookahead: Option<Sym>,
+    // item without lookahead
+    pub(crate) core: String,
+    pub(crate) text: String,
+}
+
+// State of automaton, kernel and closure items are sorted by text
+#[derive(Debug, Clone)]
+pub(crate) struct StateItems {
+    // symbol the state is entered on, None for state 0
+    pub(crate) from: Option<char>,
+    pub(crate) kernel: Vec<Item>,
+    pub(crate) closure: Vec<Item>,
+    pub(crate) edges: Vec<(char, usize)>,
+}
+
+impl StateItems {
+    // kernel items, closure items (marked by +) and transitions
+    pub(crate) fn describe(&self, i: usize) -> String {
+        let mut res = match self.from {
+            None => "state 0\n".to_string(),
+            Some(c) => format!("state {} (on {})\n", i, Sym::Normal(c)),
+        };
+        for item in &self.kernel {
+            res.push_str(&format!("    {}\n", item.text));
+        }
+        for item in &self.closure {
+            res.push_str(&format!("  + {}\n", item.text));
+        }
+        for (c, target) in &self.edges {
+            res.push_str(&format!("    {} => {}\n", Sym::Normal(*c), target));
+        }
+        res
+    }
+}
+
+pub(crate) fn state_items<'a, G, T, R>(graph: &G) -> Vec<StateItems>
+where
+    G: LRGraph<'a, T, R>,
+    T: LRNode<'a, R>,
+    R: LRRule,
+{
+    let mut all = vec![];
+    for (i, node) in graph.nodes().iter().enumerate() {
+        let g = node.gramm();
+        let items = |set: &mut dyn Iterator<Item = &R>| {
+            let mut res: Vec<Item> = set
+                .map(|x| {
+                    let (core, lookahead) = x.parts();
+                    Item {
+                        rule: core.rule,
+                        dot: core.place,
+                        lookahead,
+                        core: core.describe(g),
+                        text: x.describe(g),
+                    }
+                })
+                .collect();
+            res.sort_by(|a, b| a.text.cmp(&b.text));
+            res
+        };
+        let kernel = items(&mut node.base().iter());
+        let closure = items(&mut node.closure().difference(node.base()));
+        let mut edges: Vec<(char, usize)> =
+            graph.edges()[i].iter().map(|(c, t)| (*c, *t)).collect();
+        edges.sort();
+        all.push(StateItems {
+            from: (i != 0).then(|| node.from()),
+            kernel,
+            closure,
+            edges,
+        });
+    }
+    all
+}
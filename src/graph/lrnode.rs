@@ -1,4 +1,4 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashSet};
 
 use crate::grammar::Grammar;
 
@@ -15,10 +15,10 @@ pub trait LRNode<'a, T> : PartialEq where T: LRRule + Clone {
     fn new(base: HashSet<T>, from: char, gramm: &'a Grammar) -> Self;
     fn default(g: &'a Grammar) -> Self;
     fn create_closure(&mut self);
-    fn get_steps(&self) -> HashMap<char, Vec<T>>;
+    fn get_steps(&self) -> BTreeMap<char, Vec<T>>;
 
     fn all_rules(&self) -> HashSet<&T> {
-        self.base().union(&self.closure()).collect::<HashSet<&T>>()
+        self.base().union(self.closure()).collect::<HashSet<&T>>()
     }
 }
 
@@ -70,16 +70,16 @@ where
             acc = tmp;
             let l = self.closure.len();
             for i in acc.iter() {
-                self.closure.insert(i.clone());
+                self.closure.insert(*i);
             }
             flag = self.closure.len() != l;
         }
     }
 
-    fn get_steps(&self) -> HashMap<char, Vec<T>> {
-        let mut res: HashMap<char, Vec<T>> = HashMap::new();
+    fn get_steps(&self) -> BTreeMap<char, Vec<T>> {
+        let mut res: BTreeMap<char, Vec<T>> = BTreeMap::new();
         for rule in self.base.union(&self.closure) {
-            if let Some(c) = rule.get_sym(&self.gramm()) {
+            if let Some(c) = rule.get_sym(self.gramm()) {
                 let tmp = rule.next_rule();
                 match res.get_mut(&c) {
                     Some(v) => v.push(tmp),
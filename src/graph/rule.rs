@@ -1,7 +1,10 @@
 use std::collections::HashSet;
 use std::hash::Hash;
 
-use crate::grammar::Grammar;
+use crate::{
+    grammar::{Grammar, Sym},
+    graph::lr0rule::LR0Rule,
+};
 
 pub trait LRRule: Sized + Hash + Clone + Copy + Eq {
     fn default() -> Self;
@@ -9,5 +12,9 @@ pub trait LRRule: Sized + Hash + Clone + Copy + Eq {
     fn get_left(&self, g: &Grammar) -> Option<char>;
     fn next_rule(&self) -> Self;
     fn create_closure(&self, g: &Grammar) -> HashSet<Self>;
+    // item with dot, e.g. E -> E . + T
+    fn describe(&self, g: &Grammar) -> String;
+    // rule with place of dot and lookahead of LR(1) item
+    fn parts(&self) -> (LR0Rule, Option<Sym>);
 }
 
@@ -0,0 +1,281 @@
+//! Helpers for writing JSON without external crates
+
+/// string as JSON string literal with quotes
+pub fn string(s: &str) -> String {
+    let mut res = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => res.push_str("\\\""),
+            '\\' => res.push_str("\\\\"),
+            '\n' => res.push_str("\\n"),
+            '\t' => res.push_str("\\t"),
+            '\r' => res.push_str("\\r"),
+            c if (c as u32) < 0x20 => res.push_str(&format!("\\u{:04x}", c as u32)),
+            c => res.push(c),
+        }
+    }
+    res.push('"');
+    res
+}
+
+/// items already written as JSON joined into array
+pub fn array<I>(items: I) -> String
+where
+    I: IntoIterator<Item = String>,
+{
+    format!("[{}]", items.into_iter().collect::<Vec<String>>().join(","))
+}
+
+/// (key, value written as JSON) pairs into object
+pub fn object<'k, I>(fields: I) -> String
+where
+    I: IntoIterator<Item = (&'k str, String)>,
+{
+    let fields: Vec<String> = fields
+        .into_iter()
+        .map(|(k, v)| format!("{}:{}", string(k), v))
+        .collect();
+    format!("{{{}}}", fields.join(","))
+}
+
+/// Parsed JSON value, numbers are kept as written
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Null,
+    Bool(bool),
+    Number(String),
+    String(String),
+    Array(Vec<Value>),
+    Object(Vec<(String, Value)>),
+}
+
+impl Value {
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        match self {
+            Value::Object(fields) => fields.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn as_u64(&self) -> Option<u64> {
+        match self {
+            Value::Number(n) => n.parse().ok(),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&[Value]> {
+        match self {
+            Value::Array(items) => Some(items),
+            _ => None,
+        }
+    }
+
+    pub fn as_object(&self) -> Option<&[(String, Value)]> {
+        match self {
+            Value::Object(fields) => Some(fields),
+            _ => None,
+        }
+    }
+}
+
+/// Parses whole text as one JSON value
+pub fn parse(text: &str) -> Result<Value, String> {
+    let mut parser = Parser {
+        chars: text.chars().collect(),
+        place: 0,
+    };
+    let res = parser.value()?;
+    parser.spaces();
+    match parser.place == parser.chars.len() {
+        true => Ok(res),
+        false => Err(parser.error("end of input")),
+    }
+}
+
+struct Parser {
+    chars: Vec<char>,
+    place: usize,
+}
+
+impl Parser {
+    fn error(&self, expected: &str) -> String {
+        format!("invalid JSON at {}, expected {}", self.place, expected)
+    }
+
+    fn spaces(&mut self) {
+        while matches!(self.chars.get(self.place), Some(' ' | '\n' | '\r' | '\t')) {
+            self.place += 1;
+        }
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        self.spaces();
+        if self.chars.get(self.place) == Some(&c) {
+            self.place += 1;
+            return true;
+        }
+        false
+    }
+
+    fn word(&mut self, word: &str, value: Value) -> Result<Value, String> {
+        for c in word.chars() {
+            if self.chars.get(self.place) != Some(&c) {
+                return Err(self.error(word));
+            }
+            self.place += 1;
+        }
+        Ok(value)
+    }
+
+    fn value(&mut self) -> Result<Value, String> {
+        self.spaces();
+        match self.chars.get(self.place) {
+            Some('n') => self.word("null", Value::Null),
+            Some('t') => self.word("true", Value::Bool(true)),
+            Some('f') => self.word("false", Value::Bool(false)),
+            Some('"') => Ok(Value::String(self.string()?)),
+            Some('[') => {
+                self.place += 1;
+                let mut items = vec![];
+                if self.eat(']') {
+                    return Ok(Value::Array(items));
+                }
+                loop {
+                    items.push(self.value()?);
+                    if self.eat(']') {
+                        return Ok(Value::Array(items));
+                    }
+                    if !self.eat(',') {
+                        return Err(self.error("',' or ']'"));
+                    }
+                }
+            }
+            Some('{') => {
+                self.place += 1;
+                let mut fields = vec![];
+                if self.eat('}') {
+                    return Ok(Value::Object(fields));
+                }
+                loop {
+                    self.spaces();
+                    let key = self.string()?;
+                    if !self.eat(':') {
+                        return Err(self.error("':'"));
+                    }
+                    fields.push((key, self.value()?));
+                    if self.eat('}') {
+                        return Ok(Value::Object(fields));
+                    }
+                    if !self.eat(',') {
+                        return Err(self.error("',' or '}'"));
+                    }
+                }
+            }
+            Some(c) if *c == '-' || c.is_ascii_digit() => {
+                let start = self.place;
+                while matches!(self.chars.get(self.place), Some(c) if "+-.eE".contains(*c) || c.is_ascii_digit())
+                {
+                    self.place += 1;
+                }
+                Ok(Value::Number(
+                    self.chars[start..self.place].iter().collect(),
+                ))
+            }
+            _ => Err(self.error("value")),
+        }
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        if self.chars.get(self.place) != Some(&'"') {
+            return Err(self.error("string"));
+        }
+        self.place += 1;
+        let mut res = String::new();
+        loop {
+            let c = *self.chars.get(self.place).ok_or(self.error("'\"'"))?;
+            self.place += 1;
+            match c {
+                '"' => return Ok(res),
+                '\\' => {
+                    let e = *self.chars.get(self.place).ok_or(self.error("escape"))?;
+                    self.place += 1;
+                    match e {
+                        '"' | '\\' | '/' => res.push(e),
+                        'n' => res.push('\n'),
+                        't' => res.push('\t'),
+                        'r' => res.push('\r'),
+                        'b' => res.push('\u{8}'),
+                        'f' => res.push('\u{c}'),
+                        'u' => {
+                            let code = self.hex()?;
+                            // surrogate pair for characters outside of BMP
+                            let code = match code {
+                                0xD800..=0xDBFF if self.chars.get(self.place) == Some(&'\\') => {
+                                    self.place += 2;
+                                    let low = self.hex()?;
+                                    0x10000 + ((code - 0xD800) << 10) + (low.wrapping_sub(0xDC00))
+                                }
+                                x => x,
+                            };
+                            res.push(char::from_u32(code).ok_or(self.error("character"))?);
+                        }
+                        _ => return Err(self.error("escape")),
+                    }
+                }
+                c => res.push(c),
+            }
+        }
+    }
+
+    fn hex(&mut self) -> Result<u32, String> {
+        let digits: String = self.chars.iter().skip(self.place).take(4).collect();
+        let res = u32::from_str_radix(&digits, 16).map_err(|_| self.error("4 hex digits"))?;
+        self.place += 4;
+        Ok(res)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let text = object([
+            ("name", string("a \"b\"\n\u{E000}")),
+            (
+                "items",
+                array(["1".to_string(), "-2.5e3".to_string(), "null".to_string()]),
+            ),
+            ("empty", object([])),
+        ]);
+        let v = parse(&text).unwrap();
+        assert_eq!(v.get("name").unwrap().as_str(), Some("a \"b\"\n\u{E000}"));
+        let items = v.get("items").unwrap().as_array().unwrap();
+        assert_eq!(items[0].as_u64(), Some(1));
+        assert_eq!(items[1], Value::Number("-2.5e3".to_string()));
+        assert_eq!(items[2], Value::Null);
+        assert_eq!(v.get("empty").unwrap().as_object(), Some(&[][..]));
+
+        assert_eq!(
+            parse(" [true , false,\"\\u00e9\\ud83d\\ude00\"] ").unwrap(),
+            Value::Array(vec![
+                Value::Bool(true),
+                Value::Bool(false),
+                Value::String("é😀".to_string())
+            ])
+        );
+        assert!(parse("[1,]").is_err());
+        assert!(parse("{\"a\" 1}").is_err());
+        assert!(parse("[1] 2").is_err());
+        assert!(parse("\"abc").is_err());
+    }
+}
@@ -0,0 +1,58 @@
+//! Generator of LR(0), SLR(1), LALR(1) and LR(1) parsing tables for grammars
+//! with one character symbols, and parsers driven by the generated tables.
+//!
+//! ```
+//! use lr0_gen::{Algo, Grammar, StackAutomata};
+//!
+//! let g = Grammar::from_text("S E\na +\nS->E\nE->E+a\nE->a\n").unwrap();
+//! let table = Algo::LALR1.build(&g);
+//! let tree = StackAutomata::new(table.as_ref(), "a+a").run().unwrap();
+//! assert_eq!(tree.sexp(), "(S (E (E a) + a))");
+//! ```
+
+mod codegen;
+mod earley;
+mod glr;
+mod grammar;
+mod graph;
+pub mod json;
+mod parseerror;
+mod parsetree;
+mod repair;
+mod report;
+mod semantic;
+mod stackautomata;
+mod table;
+#[cfg(test)]
+mod testing;
+mod trace;
+
+pub use crate::{
+    codegen::{build::Build, c::emit_c, js::emit_js, rust::emit_rust},
+    earley::{compare, Disagreement, Earley},
+    glr::{
+        sppf::{Forest, PackedNode, SymbolNode},
+        GLRParser,
+    },
+    grammar::{Grammar, Rule, Sym, ERROR},
+    parseerror::ParseError,
+    parsetree::{ParseTree, Preorder, TreeBuilder},
+    repair::Repair,
+    report::{
+        bison::bison_report,
+        export::{json_report, ANALYSIS_SCHEMA},
+        html::html_report,
+    },
+    semantic::{Actions, Semantics},
+    stackautomata::StackAutomata,
+    table::{
+        algo::{conflicts, Algo, Conflict},
+        classic::ClassicTable,
+        compressed::{CompressedTable, Compression},
+        dense::DenseTable,
+        lrtable::{Action, ClassicAction, Table},
+        render::{renderer, Csv, Html, Latex, Markdown, Renderer, Text},
+        stored::StoredTable,
+    },
+    trace::{Trace, TraceAction, TraceStep},
+};
@@ -0,0 +1,59 @@
+use std::fmt::Display;
+
+use crate::grammar::Sym;
+
+/// Syntax error found by StackAutomata, found is Sym::Eps
+/// when automata got to the end of input
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub offset: usize,
+    pub found: Sym,
+    pub state: usize,
+    /// terminals with non error action in state
+    pub expected: Vec<Sym>,
+    input: String,
+}
+
+fn describe(sym: &Sym) -> String {
+    match sym {
+        Sym::Normal(_) => format!("'{}'", sym),
+        Sym::Eps => "end of input".to_string(),
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "error: unexpected {} at {} in state {}",
+            describe(&self.found),
+            self.offset,
+            self.state
+        )?;
+        if !self.expected.is_empty() {
+            let expected: Vec<String> = self.expected.iter().map(describe).collect();
+            write!(f, ", expected {}", expected.join(", "))?;
+        }
+        writeln!(f)?;
+        writeln!(f, "{}", self.input)?;
+        write!(f, "{}^", " ".repeat(self.offset))
+    }
+}
+
+impl ParseError {
+    pub(crate) fn new(
+        input: &[char],
+        offset: usize,
+        found: Sym,
+        state: usize,
+        expected: Vec<Sym>,
+    ) -> Self {
+        Self {
+            offset,
+            found,
+            state,
+            expected,
+            input: input.iter().collect(),
+        }
+    }
+}
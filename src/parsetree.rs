@@ -0,0 +1,137 @@
+use std::fmt::{Display, Write};
+
+use crate::{grammar::Sym, semantic::Semantics};
+
+// Concrete syntax tree built by StackAutomata, leaves are shifted
+// terminals with their offset in input, nodes are reduced rules
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseTree {
+    Leaf {
+        sym: char,
+        offset: usize,
+    },
+    Node {
+        sym: char,
+        rule: usize,
+        children: Vec<ParseTree>,
+    },
+}
+
+impl Display for ParseTree {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.sexp())
+    }
+}
+
+impl ParseTree {
+    pub fn sym(&self) -> char {
+        match self {
+            ParseTree::Leaf { sym, .. } | ParseTree::Node { sym, .. } => *sym,
+        }
+    }
+
+    pub fn rule(&self) -> Option<usize> {
+        match self {
+            ParseTree::Leaf { .. } => None,
+            ParseTree::Node { rule, .. } => Some(*rule),
+        }
+    }
+
+    pub fn children(&self) -> &[ParseTree] {
+        match self {
+            ParseTree::Leaf { .. } => &[],
+            ParseTree::Node { children, .. } => children,
+        }
+    }
+
+    // nodes with their depth, parent goes before its children
+    pub fn preorder(&self) -> Preorder<'_> {
+        Preorder {
+            stack: vec![(0, self)],
+        }
+    }
+
+    // part of input covered by tree
+    pub fn text(&self) -> String {
+        self.preorder()
+            .filter(|(_, x)| x.rule().is_none())
+            .map(|(_, x)| x.sym())
+            .collect()
+    }
+
+    pub fn indented(&self) -> String {
+        let mut res = String::new();
+        for (depth, node) in self.preorder() {
+            let _ = match node {
+                ParseTree::Leaf { sym, offset } => {
+                    writeln!(
+                        res,
+                        "{}{} @{}",
+                        "  ".repeat(depth),
+                        Sym::Normal(*sym),
+                        offset
+                    )
+                }
+                ParseTree::Node { sym, rule, .. } => writeln!(
+                    res,
+                    "{}{} ({}) \"{}\"",
+                    "  ".repeat(depth),
+                    sym,
+                    rule,
+                    node.text()
+                ),
+            };
+        }
+        res
+    }
+
+    pub fn sexp(&self) -> String {
+        match self {
+            ParseTree::Leaf { sym, .. } => Sym::Normal(*sym).to_string(),
+            ParseTree::Node { sym, children, .. } => {
+                let mut res = format!("({}", sym);
+                for c in children {
+                    res.push(' ');
+                    res.push_str(&c.sexp());
+                }
+                res.push(')');
+                res
+            }
+        }
+    }
+}
+
+/// Default semantics of StackAutomata
+pub struct TreeBuilder;
+
+impl Semantics for TreeBuilder {
+    type Value = ParseTree;
+
+    fn on_shift(&mut self, sym: char, offset: usize) -> ParseTree {
+        ParseTree::Leaf { sym, offset }
+    }
+
+    fn on_reduce(&mut self, rule: usize, left: char, values: Vec<ParseTree>) -> ParseTree {
+        ParseTree::Node {
+            sym: left,
+            rule,
+            children: values,
+        }
+    }
+}
+
+pub struct Preorder<'a> {
+    stack: Vec<(usize, &'a ParseTree)>,
+}
+
+impl<'a> Iterator for Preorder<'a> {
+    type Item = (usize, &'a ParseTree);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (depth, node) = self.stack.pop()?;
+        for c in node.children().iter().rev() {
+            self.stack.push((depth + 1, c));
+        }
+        Some((depth, node))
+    }
+}
@@ -0,0 +1,373 @@
+use std::fmt::Display;
+
+use crate::{
+    grammar::{Grammar, Sym, ERROR},
+    table::lrtable::{Action, Table},
+};
+
+/// Change of input suggested by StackAutomata, offsets
+/// are positions in the original input
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Repair {
+    Insert { offset: usize, sym: char },
+    Delete { offset: usize, sym: char },
+    Substitute { offset: usize, old: char, new: char },
+}
+
+impl Display for Repair {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Repair::Insert { offset, sym } => {
+                write!(f, "insert `{}` at {}", Sym::Normal(*sym), offset)
+            }
+            Repair::Delete { offset, sym } => {
+                write!(f, "delete `{}` at {}", Sym::Normal(*sym), offset)
+            }
+            Repair::Substitute { offset, old, new } => write!(
+                f,
+                "replace `{}` with `{}` at {}",
+                Sym::Normal(*old),
+                Sym::Normal(*new),
+                offset
+            ),
+        }
+    }
+}
+
+// Most edits tried in one repair
+const MAX_COST: usize = 3;
+
+#[derive(Debug, Clone, Copy)]
+enum Edit {
+    Insert(char),
+    Substitute(char),
+    Delete,
+}
+
+// Result of the search, place is the first input symbol
+// after the repaired part and emitted replaces input[start..place]
+pub(crate) struct Found {
+    pub(crate) repairs: Vec<Repair>,
+    pub(crate) emitted: Vec<char>,
+    pub(crate) place: usize,
+}
+
+// Local repair searching for cheapest sequence of edits at place
+// after which parser gets `check` more input symbols without error,
+// at least one is checked so every repair makes progress
+pub(crate) struct RepairSearch<'a> {
+    table: &'a dyn Table,
+    gramm: &'a Grammar,
+    terms: Vec<char>,
+    check: usize,
+}
+
+impl<'a> RepairSearch<'a> {
+    pub(crate) fn new(table: &'a dyn Table, check: usize) -> Self {
+        let gramm = table.grammar();
+        let mut terms: Vec<char> = gramm
+            .terms
+            .iter()
+            .copied()
+            .filter(|x| *x != ERROR)
+            .collect();
+        terms.sort();
+        Self {
+            table,
+            gramm,
+            terms,
+            check: check.max(1),
+        }
+    }
+
+    // offset is position of input[place] in the original input
+    pub(crate) fn find(
+        &self,
+        stack: &[usize],
+        input: &[char],
+        place: usize,
+        offset: usize,
+    ) -> Option<Found> {
+        for cost in 1..=MAX_COST {
+            let mut edits = vec![];
+            if let Some(res) = self.search(stack, input, place, cost, &mut edits) {
+                return Some(self.found(input, place, offset, &res));
+            }
+        }
+        None
+    }
+
+    fn search(
+        &self,
+        stack: &[usize],
+        input: &[char],
+        place: usize,
+        cost: usize,
+        edits: &mut Vec<Edit>,
+    ) -> Option<Vec<Edit>> {
+        let (emitted, cursor) = Self::apply(place, edits);
+        if edits.len() == cost {
+            let rest = input[cursor..].iter().take(self.check).copied();
+            let tokens: Vec<char> = emitted.iter().copied().chain(rest).collect();
+            let end = cursor + self.check >= input.len();
+            return match self.parse_ahead(stack.to_vec(), &tokens, end) {
+                true => Some(edits.clone()),
+                false => None,
+            };
+        }
+        // prefix that can not be parsed can not be fixed later
+        if !emitted.is_empty() && !self.parse_ahead(stack.to_vec(), &emitted, false) {
+            return None;
+        }
+
+        let mut options: Vec<Edit> = self.terms.iter().map(|x| Edit::Insert(*x)).collect();
+        if cursor < input.len() {
+            options.extend(
+                self.terms
+                    .iter()
+                    .filter(|x| **x != input[cursor])
+                    .map(|x| Edit::Substitute(*x)),
+            );
+            options.push(Edit::Delete);
+        }
+        for e in options {
+            edits.push(e);
+            let res = self.search(stack, input, place, cost, edits);
+            edits.pop();
+            if res.is_some() {
+                return res;
+            }
+        }
+        None
+    }
+
+    fn apply(place: usize, edits: &[Edit]) -> (Vec<char>, usize) {
+        let mut emitted = vec![];
+        let mut cursor = place;
+        for e in edits {
+            match e {
+                Edit::Insert(c) => emitted.push(*c),
+                Edit::Substitute(c) => {
+                    emitted.push(*c);
+                    cursor += 1;
+                }
+                Edit::Delete => cursor += 1,
+            }
+        }
+        (emitted, cursor)
+    }
+
+    fn found(&self, input: &[char], place: usize, offset: usize, edits: &[Edit]) -> Found {
+        let mut repairs = vec![];
+        let mut cursor = place;
+        for e in edits {
+            let at = offset + cursor - place;
+            match e {
+                Edit::Insert(c) => repairs.push(Repair::Insert {
+                    offset: at,
+                    sym: *c,
+                }),
+                Edit::Substitute(c) => {
+                    repairs.push(Repair::Substitute {
+                        offset: at,
+                        old: input[cursor],
+                        new: *c,
+                    });
+                    cursor += 1;
+                }
+                Edit::Delete => {
+                    repairs.push(Repair::Delete {
+                        offset: at,
+                        sym: input[cursor],
+                    });
+                    cursor += 1;
+                }
+            }
+        }
+        let (emitted, place) = Self::apply(place, edits);
+        Found {
+            repairs,
+            emitted,
+            place,
+        }
+    }
+
+    // runs table on copy of stack, succeeds when all tokens are shifted
+    // (and input is accepted after them if they are the end of input)
+    // within a bounded number of steps
+    fn parse_ahead(&self, mut stack: Vec<usize>, tokens: &[char], end: bool) -> bool {
+        let mut i = 0;
+        // cyclic chain of reductions in a broken table would never end
+        for _ in 0..(tokens.len() + 1) * (self.table.states() + 1) {
+            let state = match stack.last() {
+                Some(s) => *s,
+                None => return false,
+            };
+            if i == tokens.len() && !end {
+                return true;
+            }
+            let sym = match tokens.get(i) {
+                Some(c) => Sym::Normal(*c),
+                None => Sym::Eps,
+            };
+            match self.table.get_action(state, sym) {
+                Some(Action::Shift) if i < tokens.len() => {
+                    match self.table.get_goto(state, tokens[i]) {
+                        Some(target) => {
+                            stack.push(target);
+                            i += 1;
+                        }
+                        None => return false,
+                    }
+                }
+                Some(Action::Accept) => return i == tokens.len(),
+                Some(Action::Reduction(r)) => {
+                    let rule = &self.gramm.rules[r];
+                    if stack.len() <= rule.right.len() {
+                        return false;
+                    }
+                    stack.truncate(stack.len() - rule.right.len());
+                    match self.table.get_goto(*stack.last().unwrap(), rule.left) {
+                        Some(target) => stack.push(target),
+                        None => return false,
+                    }
+                }
+                _ => return false,
+            }
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        graph::{
+            lr1graph::LR1Node,
+            lrgraph::{LR1Graph, LRGraph},
+            lrnode::LRNode,
+        },
+        stackautomata::StackAutomata,
+        table::{algo::Algo, lr1table::LR1Table},
+    };
+
+    use super::*;
+
+    fn repair(file: &str, input: &str) -> (Option<String>, Vec<String>) {
+        repair_checking(file, input, 3)
+    }
+
+    fn repair_checking(file: &str, input: &str, check: usize) -> (Option<String>, Vec<String>) {
+        let g = Grammar::from_text(file).unwrap();
+        let mut graph = LR1Graph::new();
+        graph.construct(LR1Node::default(&g));
+        let table = LR1Table::new(graph, &g);
+        let mut autom = StackAutomata::new(&table, input);
+        let (tree, repairs) = autom.run_repairing(check);
+        (
+            tree.ok().map(|x| x.text()),
+            repairs.iter().map(|x| x.to_string()).collect(),
+        )
+    }
+
+    #[test]
+    fn single() {
+        let gram = include_str!("../examples/example.gram");
+        assert_eq!(
+            repair(gram, "a+*a"),
+            (
+                Some("a+a*a".to_string()),
+                vec!["insert `a` at 2".to_string()]
+            )
+        );
+        assert_eq!(
+            repair(gram, "(a+a"),
+            (
+                Some("(a+a)".to_string()),
+                vec!["insert `)` at 4".to_string()]
+            )
+        );
+        assert_eq!(
+            repair(gram, "a)+a"),
+            (Some("a+a".to_string()), vec!["delete `)` at 1".to_string()])
+        );
+        assert_eq!(repair(gram, "a+a"), (Some("a+a".to_string()), vec![]));
+    }
+
+    #[test]
+    fn multiple() {
+        let gram = include_str!("../examples/example.gram");
+        let (text, repairs) = repair(gram, "a+*a)*(a");
+        assert_eq!(text, Some("a+(a)*(a)".to_string()));
+        assert_eq!(
+            repairs,
+            vec!["replace `*` with `(` at 2", "insert `)` at 8"]
+        );
+
+        let (text, repairs) = repair(include_str!("../examples/simple.gram"), "aab");
+        assert_eq!(text, Some("aabb".to_string()));
+        assert_eq!(repairs, vec!["insert `b` at 3"]);
+    }
+
+    #[test]
+    fn no_check() {
+        // nothing after the edits is checked, one symbol still is
+        let gram = include_str!("../examples/example.gram");
+        assert_eq!(
+            repair_checking(gram, "a+*a", 0),
+            repair_checking(gram, "a+*a", 1)
+        );
+        assert_eq!(
+            repair_checking(gram, "(a", 0),
+            repair_checking(gram, "(a", 1)
+        );
+        assert_eq!(repair_checking(gram, "(a", 0).0, Some("(a)".to_string()));
+    }
+
+    // reduces A -> A on end of input forever
+    struct Cycle(Box<dyn Table>);
+
+    impl Display for Cycle {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            self.0.fmt(f)
+        }
+    }
+
+    impl Table for Cycle {
+        fn get_action(&self, state: usize, sym: Sym) -> Option<Action> {
+            match sym {
+                Sym::Eps => Some(Action::Reduction(2)),
+                _ => self.0.get_action(state, sym),
+            }
+        }
+
+        fn get_goto(&self, state: usize, chr: char) -> Option<usize> {
+            self.0.get_goto(state, chr)
+        }
+
+        fn get_char(&self, state: usize) -> Option<char> {
+            self.0.get_char(state)
+        }
+
+        fn states(&self) -> usize {
+            self.0.states()
+        }
+
+        fn grammar(&self) -> &Grammar {
+            self.0.grammar()
+        }
+
+        fn name(&self) -> &str {
+            self.0.name()
+        }
+    }
+
+    #[test]
+    fn bounded() {
+        let g = Grammar::from_text("S A\na\nS->A\nA->a\nA->A\n").unwrap();
+        let table = Cycle(Algo::LR1.build(&g));
+        let a = table.get_goto(0, 'A').unwrap();
+        let search = RepairSearch::new(&table, 3);
+        assert!(!search.parse_ahead(vec![0, a], &[], true));
+    }
+}
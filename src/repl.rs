@@ -0,0 +1,312 @@
+use std::io::{self, BufRead, Write};
+
+use lr0_gen::{conflicts, Action, Algo, Grammar, StackAutomata, Sym, Table};
+
+use crate::cli::{first_follow, Format};
+
+const HELP: &str = "commands:
+  algo [lr0|slr1|lalr1|lr1]  show or switch construction of table
+  rules                      numbered rules of grammar
+  add A->xyz                 add rule and regenerate table
+  remove N                   remove rule N (not 0) and regenerate table
+  table                      print table
+  conflicts                  conflicting cells of table
+  first-follow               nullable, FIRST and FOLLOW of non terminals
+  state N                    items and transitions of state N
+  row N                      actions and gotos of state N
+  parse TEXT                 parse TEXT with trace
+  step TEXT                  start stepping through parse of TEXT
+  next                       do one action of stepped parse (or empty line)
+  help                       this help
+  quit                       exit";
+
+// Grammar explorer, table is built again when grammar
+// or algorithm is changed
+pub(crate) struct Repl {
+    gramm: Grammar,
+    algo: Algo,
+    table: Box<dyn Table>,
+    // input and number of actions done while stepping
+    stepping: Option<(String, usize)>,
+}
+
+impl Repl {
+    pub(crate) fn new(gramm: Grammar, algo: Algo) -> Self {
+        Self {
+            table: algo.build(&gramm),
+            gramm,
+            algo,
+            stepping: None,
+        }
+    }
+
+    pub(crate) fn run(&mut self) -> io::Result<()> {
+        let stdin = io::stdin();
+        let mut stdout = io::stdout();
+        println!("{}\n{}", self.summary(), HELP);
+        loop {
+            print!("> ");
+            stdout.flush()?;
+            let mut line = String::new();
+            if stdin.lock().read_line(&mut line)? == 0 {
+                return Ok(());
+            }
+            match self.execute(line.trim_end_matches(['\n', '\r'])) {
+                Some(out) => print!("{}", out),
+                None => return Ok(()),
+            }
+        }
+    }
+
+    // output of one command, None for quit
+    pub(crate) fn execute(&mut self, line: &str) -> Option<String> {
+        let (command, arg) = match line.split_once(' ') {
+            Some((c, a)) => (c, a),
+            None => (line, ""),
+        };
+        let res = match command {
+            "" | "next" => self.next(),
+            "quit" | "exit" => return None,
+            "help" => Ok(format!("{}\n", HELP)),
+            "algo" if arg.is_empty() => Ok(format!("{}\n", self.algo)),
+            "algo" => arg.parse().map(|x| {
+                self.algo = x;
+                self.changed()
+            }),
+            "rules" => Ok(self.rules()),
+            "add" => match self.gramm.add_rule_text(arg) {
+                Ok(()) => Ok(self.changed()),
+                Err(e) => Err(e.to_string()),
+            },
+            "remove" => self.number(arg).and_then(|x| {
+                self.gramm.remove_rule(x).map_err(|e| e.to_string())?;
+                Ok(self.changed())
+            }),
+            "table" => Ok(format!("{}\n", self.table)),
+            "conflicts" => Ok(self.conflicts()),
+            "first-follow" => Ok(first_follow(&self.gramm, Format::Text)),
+            "state" => self.number(arg).and_then(|x| self.state(x)),
+            "row" => self.number(arg).and_then(|x| self.row(x)),
+            "parse" => Ok(self.parse(arg)),
+            "step" => Ok(self.step(arg)),
+            x => Err(format!("unknown command {}, see help", x)),
+        };
+        Some(match res {
+            Ok(x) => x,
+            Err(e) => format!("error: {}\n", e),
+        })
+    }
+
+    fn number(&self, arg: &str) -> Result<usize, String> {
+        arg.trim()
+            .parse()
+            .map_err(|_| format!("{} is not a number", arg))
+    }
+
+    fn summary(&self) -> String {
+        format!(
+            "{} table with {} states and {} conflicts",
+            self.algo,
+            self.table.states(),
+            conflicts(self.table.as_ref()).len()
+        )
+    }
+
+    // grammar or algorithm changed, stepped parse is not valid anymore
+    fn changed(&mut self) -> String {
+        self.gramm.update_sets();
+        self.table = self.algo.build(&self.gramm);
+        self.stepping = None;
+        format!("{}\n", self.summary())
+    }
+
+    fn rules(&self) -> String {
+        let mut res = String::new();
+        for (i, r) in self.gramm.rules().iter().enumerate() {
+            res.push_str(&format!("{} : {}\n", i, r));
+        }
+        res
+    }
+
+    fn conflicts(&self) -> String {
+        let found = conflicts(self.table.as_ref());
+        match found.is_empty() {
+            true => "no conflicts\n".to_string(),
+            false => found.iter().map(|x| format!("{}\n", x)).collect(),
+        }
+    }
+
+    fn state(&self, state: usize) -> Result<String, String> {
+        self.algo
+            .states(&self.gramm)
+            .get(state)
+            .cloned()
+            .ok_or(format!("no state {}", state))
+    }
+
+    fn row(&self, state: usize) -> Result<String, String> {
+        let table = self.table.as_ref();
+        if state >= table.states() {
+            return Err(format!("no state {}", state));
+        }
+        let mut terms: Vec<char> = self.gramm.terms().iter().copied().collect();
+        terms.sort();
+        let mut syms: Vec<char> = self.gramm.non_terms().iter().copied().collect();
+        syms.sort();
+        syms.extend(terms.iter());
+
+        let mut actions = vec![];
+        for s in terms.into_iter().map(Sym::Normal).chain([Sym::Eps]) {
+            let all: Vec<String> = table
+                .get_actions(state, s)
+                .iter()
+                .map(|x| x.to_string())
+                .collect();
+            if !all.is_empty() {
+                actions.push(format!("{} {}", s, all.join("/")));
+            }
+        }
+        let gotos: Vec<String> = syms
+            .iter()
+            .filter_map(|c| {
+                let target = table.get_goto(state, *c)?;
+                Some(format!("{} {}", Sym::Normal(*c), target))
+            })
+            .collect();
+        Ok(format!(
+            "action : {}\ngoto   : {}\n",
+            actions.join(", "),
+            gotos.join(", ")
+        ))
+    }
+
+    fn parse(&self, input: &str) -> String {
+        let mut autom = StackAutomata::new(self.table.as_ref(), input);
+        autom.enable_trace();
+        let (tree, errors) = autom.run_recovering();
+        let mut res = format!("{}", autom.trace().unwrap());
+        for e in errors {
+            res.push_str(&format!("{}\n", e));
+        }
+        if let Some(tree) = tree {
+            res.push_str(&tree.indented());
+        }
+        res
+    }
+
+    // starting configuration
+    fn step(&mut self, input: &str) -> String {
+        let autom = StackAutomata::new(self.table.as_ref(), input);
+        self.stepping = Some((input.to_string(), 0));
+        format!("{}", autom)
+    }
+
+    // automaton borrows the table, so only the input and the number
+    // of actions done are kept and the parse is rebuilt from them
+    fn next(&mut self) -> Result<String, String> {
+        let (input, done) = match &self.stepping {
+            Some(x) => x.clone(),
+            None => return Err("nothing to step, use step TEXT".to_string()),
+        };
+        let mut autom = StackAutomata::new(self.table.as_ref(), &input);
+        for _ in 0..done {
+            autom
+                .step()
+                .map_err(|_| "stepped parse can not be replayed".to_string())?;
+        }
+        autom.enable_trace();
+        let res = autom.step();
+        let step = &autom.trace().unwrap().steps[0];
+        let mut out = format!("{}\n", step);
+        match res {
+            Ok(Action::Accept) => {
+                self.stepping = None;
+                out.push_str("accepted\n");
+            }
+            Ok(_) => self.stepping = Some((input, done + 1)),
+            Err(e) => {
+                self.stepping = None;
+                out.push_str(&format!("{}\n", e));
+            }
+        }
+        Ok(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn repl() -> Repl {
+        let g = Grammar::from_text(include_str!("../examples/simple.gram")).unwrap();
+        Repl::new(g, Algo::LR1)
+    }
+
+    #[test]
+    fn explore() {
+        let mut r = repl();
+        assert_eq!(r.execute("algo").unwrap(), "lr1\n");
+        assert_eq!(
+            r.execute("rules").unwrap(),
+            "0 : S -> A\n1 : A -> a A b\n2 : A -> eps\n"
+        );
+        assert_eq!(
+            r.execute("row 0").unwrap(),
+            "action : a S, eps R2\ngoto   : A 1, a 2\n"
+        );
+        assert!(r
+            .execute("state 0")
+            .unwrap()
+            .starts_with("state 0\n    [S -> . A, eps]\n"));
+        assert!(r
+            .execute("state 99")
+            .unwrap()
+            .starts_with("error: no state 99"));
+        assert!(r.execute("parse ab").unwrap().contains("A (1) \"ab\""));
+        assert!(r.execute("frobnicate").unwrap().starts_with("error:"));
+        assert_eq!(r.execute("quit"), None);
+    }
+
+    #[test]
+    fn change_grammar() {
+        let mut r = repl();
+        assert_eq!(
+            r.execute("add A->aA").unwrap(),
+            "lr1 table with 8 states and 1 conflicts\n"
+        );
+        assert_eq!(r.execute("conflicts").unwrap(), "state 6 on b : S/R3\n");
+        assert_eq!(
+            r.execute("remove 3").unwrap(),
+            "lr1 table with 8 states and 0 conflicts\n"
+        );
+        assert!(r.execute("add B->a").unwrap().starts_with("error:"));
+        assert_eq!(
+            r.execute("algo lr0").unwrap(),
+            "lr0 table with 5 states and 2 conflicts\n"
+        );
+    }
+
+    #[test]
+    fn stepping() {
+        let mut r = repl();
+        assert!(r.execute("next").unwrap().starts_with("error:"));
+        assert_eq!(
+            r.execute("step ab").unwrap(),
+            "stack : 0 \ninput : a b \nresult : \n"
+        );
+        assert_eq!(
+            r.execute("").unwrap(),
+            "states : 0 | symbols :  | input : ab$ | shift 2\n"
+        );
+        assert!(r.execute("next").unwrap().contains("reduce 2"));
+        r.execute("next");
+        r.execute("next");
+        assert!(r.execute("next").unwrap().ends_with("accept\naccepted\n"));
+        assert!(r.execute("next").unwrap().starts_with("error:"));
+
+        // new table ends the stepped parse
+        r.execute("step ab");
+        r.execute("algo slr1");
+        assert!(r.execute("next").unwrap().starts_with("error:"));
+    }
+}
@@ -0,0 +1,264 @@
+use crate::{
+    grammar::{Grammar, Sym, ERROR},
+    report::Analysis,
+    table::{
+        algo::Algo,
+        classic::ClassicTable,
+        compressed::CompressedTable,
+        lrtable::{sorted, ClassicAction, Table},
+    },
+};
+
+/// Verbose report in the layout of Bison's `.output` file: conflicts
+/// per state, the grammar, terminals and non-terminals with rules they
+/// appear in, and every state of the automaton built by `algo` with its
+/// kernel items, shifts, reductions (`$default` one included) and gotos.
+/// Terminals are quoted (`'a'`), end of input is `$end`. Conflicts are
+/// shown the way Bison resolves them: shift wins and otherwise the
+/// smaller rule, the other actions are in brackets.
+pub fn bison_report(g: &Grammar, algo: Algo) -> String {
+    let a = Analysis::new(g, algo);
+    let table = ClassicTable::new(a.table.as_ref());
+    let defaults = CompressedTable::new(&table);
+    let name = |x: Sym| match x {
+        Sym::Eps => "$end".to_string(),
+        Sym::Normal(ERROR) => "error".to_string(),
+        Sym::Normal(c) if g.is_term(&c) => match c {
+            '\'' | '\\' => format!("'\\{}'", c),
+            c => format!("'{}'", c),
+        },
+        Sym::Normal(c) => c.to_string(),
+    };
+    // end of input first, as Bison numbers it 0
+    let lookahead: Vec<Sym> = [Sym::Eps]
+        .into_iter()
+        .chain(sorted(g.terms()).into_iter().map(Sym::Normal))
+        .collect();
+
+    let mut res = String::new();
+    let mut any = false;
+    for s in 0..table.states() {
+        let (mut sr, mut rr) = (0, 0);
+        for x in &lookahead {
+            let all = table.action(s, *x);
+            let reduces = all
+                .iter()
+                .filter(|y| matches!(y, ClassicAction::Reduce(_)))
+                .count();
+            match all.iter().any(|y| matches!(y, ClassicAction::Shift(_))) {
+                true => sr += reduces,
+                false => rr += reduces.saturating_sub(1),
+            }
+        }
+        let counts: Vec<String> = [(sr, "shift/reduce"), (rr, "reduce/reduce")]
+            .into_iter()
+            .filter(|(n, _)| *n > 0)
+            .map(|(n, kind)| format!("{} {}", n, kind))
+            .collect();
+        if !counts.is_empty() {
+            res.push_str(&format!("State {} conflicts: {}\n", s, counts.join(", ")));
+            any = true;
+        }
+    }
+    if any {
+        res.push_str("\n\n");
+    }
+
+    res.push_str("Grammar\n");
+    let mut last = None;
+    for (i, r) in g.rules().iter().enumerate() {
+        if last != Some(r.left()) {
+            res.push('\n');
+        }
+        res.push_str(&rule_line(g, i, None, last, &name));
+        last = Some(r.left());
+    }
+
+    res.push_str("\n\nTerminals, with rules where they appear\n\n");
+    let used = |c: char, left: bool| -> Vec<String> {
+        let rules = g.rules().iter().enumerate();
+        let rules = rules.filter(|(_, r)| match left {
+            true => r.left() == c,
+            false => r.right().contains(&c),
+        });
+        rules.map(|(i, _)| i.to_string()).collect()
+    };
+    res.push_str("    $end (0)\n");
+    for c in sorted(g.terms()) {
+        let code = if c == ERROR { 256 } else { c as u32 };
+        let rules = used(c, false);
+        res.push_str(&format!("    {} ({})", name(Sym::Normal(c)), code));
+        match rules.is_empty() {
+            true => res.push('\n'),
+            false => res.push_str(&format!(" {}\n", rules.join(" "))),
+        }
+    }
+
+    res.push_str("\n\nNonterminals, with rules where they appear\n\n");
+    for (i, c) in sorted(g.non_terms()).into_iter().enumerate() {
+        // symbol numbers continue after end of input and terminals
+        res.push_str(&format!("    {} ({})\n", c, g.terms().len() + 1 + i));
+        for (left, label) in [(true, "on left"), (false, "on right")] {
+            let rules = used(c, left);
+            if !rules.is_empty() {
+                res.push_str(&format!("        {}: {}\n", label, rules.join(" ")));
+            }
+        }
+    }
+
+    for (s, state) in a.states.iter().enumerate() {
+        res.push_str(&format!("\n\nState {}\n\n", s));
+        let mut kernel: Vec<(usize, usize)> =
+            state.kernel.iter().map(|x| (x.rule, x.dot)).collect();
+        kernel.sort();
+        kernel.dedup();
+        let mut last = None;
+        for (rule, dot) in kernel {
+            res.push_str(&rule_line(g, rule, Some(dot), last, &name));
+            last = Some(g.rules()[rule].left());
+        }
+
+        let mut shifts = vec![];
+        let mut reductions = vec![];
+        let reduce = |r: usize, enabled: bool| {
+            let text = format!("reduce using rule {} ({})", r, g.rules()[r].left());
+            match enabled {
+                true => text,
+                false => format!("[{}]", text),
+            }
+        };
+        let default = defaults.default_reduction(s);
+        for x in &lookahead {
+            let all = table.action(s, *x);
+            let mut rules: Vec<usize> = all
+                .iter()
+                .filter_map(|y| match y {
+                    ClassicAction::Reduce(r) => Some(*r),
+                    _ => None,
+                })
+                .collect();
+            rules.sort();
+            let mut taken = false;
+            for y in all {
+                match y {
+                    ClassicAction::Shift(t) => {
+                        shifts.push((name(*x), format!("shift, and go to state {}", t)));
+                        taken = true;
+                    }
+                    ClassicAction::Accept => {
+                        reductions.push((name(*x), "accept".to_string()));
+                        taken = true;
+                    }
+                    ClassicAction::Reduce(_) => (),
+                }
+            }
+            // the first reduction wins unless something was taken, default
+            // one is written out only when disabled ones follow it
+            let mut defaulted = None;
+            for r in rules {
+                match taken {
+                    false if Some(r) == default => defaulted = Some(r),
+                    false => reductions.push((name(*x), reduce(r, true))),
+                    true => {
+                        if let Some(d) = defaulted.take() {
+                            reductions.push((name(*x), reduce(d, true)));
+                        }
+                        reductions.push((name(*x), reduce(r, false)));
+                    }
+                }
+                taken = true;
+            }
+        }
+        if let Some(r) = default {
+            reductions.push(("$default".to_string(), reduce(r, true)));
+        }
+        let gotos: Vec<(String, String)> = sorted(g.non_terms())
+            .into_iter()
+            .filter_map(|c| Some((c.to_string(), format!("go to state {}", table.goto(s, c)?))))
+            .collect();
+        for block in [shifts, reductions, gotos] {
+            let width = block.iter().map(|(x, _)| x.chars().count()).max();
+            if let Some(width) = width {
+                res.push('\n');
+                for (x, text) in block {
+                    res.push_str(&format!("    {:<width$}  {}\n", x, text, width = width));
+                }
+            }
+        }
+    }
+    res
+}
+
+// numbered rule or item (with dot), left side is replaced by bar when it
+// is the same as on the previous line
+fn rule_line(
+    g: &Grammar,
+    rule: usize,
+    dot: Option<usize>,
+    last: Option<char>,
+    name: &dyn Fn(Sym) -> String,
+) -> String {
+    let r = &g.rules()[rule];
+    let mut res = match last == Some(r.left()) {
+        true => format!("  {:>3}  |", rule),
+        false => format!("  {:>3} {}:", rule, r.left()),
+    };
+    let mut right: Vec<String> = r.right().iter().map(|c| name(Sym::Normal(*c))).collect();
+    if right.is_empty() {
+        right.push("%empty".to_string());
+    }
+    if let Some(dot) = dot {
+        right.insert(dot, ".".to_string());
+    }
+    for x in right {
+        res.push(' ');
+        res.push_str(&x);
+    }
+    res.push('\n');
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shift_reduce() {
+        let g = Grammar::from_text(include_str!("../../examples/assign.gram")).unwrap();
+        let out = bison_report(&g, Algo::SLR1);
+        assert!(out.starts_with("State 6 conflicts: 1 shift/reduce\n\n\nGrammar\n\n    0 S: A\n"));
+        assert!(out.contains("\n    1 A: L '=' R\n    2  | R\n"));
+        assert!(out.contains("\n    '=' (61) 1\n"));
+        assert!(out.contains("\n    L (5)\n        on left: 3 4\n        on right: 1 5\n"));
+        assert!(out.contains(
+            "State 6\n\n    1 A: L . '=' R\n    5 R: L .\n\n\
+             \x20   '='  shift, and go to state 7\n\n\
+             \x20   '='       [reduce using rule 5 (R)]\n\
+             \x20   $default  reduce using rule 5 (R)\n"
+        ));
+        assert!(out.contains("State 5\n\n    0 S: A .\n\n    $end  accept\n"));
+        assert!(out.contains("\n    A  go to state 5\n    L  go to state 6\n"));
+
+        let out = bison_report(&g, Algo::LALR1);
+        assert!(out.starts_with("Grammar\n"));
+        // LR(1) items of one core are one line
+        assert_eq!(out.matches("\n    5 R: L .\n").count(), 2);
+    }
+
+    #[test]
+    fn reduce_reduce() {
+        let g = Grammar::from_text("Z S A B\na b\nZ->S\nS->A\nS->Bb\nA->a\nB->a\nB->\n").unwrap();
+        let out = bison_report(&g, Algo::LALR1);
+        assert!(out.contains("\n    5  | %empty\n"));
+        assert!(out.contains(
+            "\n    'a'  shift, and go to state 5\n\n    $default  reduce using rule 5 (B)\n"
+        ));
+
+        let g = Grammar::from_text("Z S A B\na\nZ->S\nS->A\nS->B\nA->a\nB->a\n").unwrap();
+        let out = bison_report(&g, Algo::LR1);
+        assert!(out.starts_with("State 4 conflicts: 1 reduce/reduce\n"));
+        assert!(out.contains(
+            "\n    $end  reduce using rule 3 (A)\n    $end  [reduce using rule 4 (B)]\n"
+        ));
+    }
+}
@@ -0,0 +1,391 @@
+use crate::{
+    grammar::{Grammar, Sym},
+    graph::lrgraph::Item,
+    json,
+    report::{sorted_syms, Analysis},
+    table::{
+        algo::Algo,
+        lrtable::{lookahead, sorted, Action},
+    },
+};
+
+const FORMAT: &str = "lr0_gen analysis";
+const VERSION: u64 = 1;
+
+/// JSON Schema of the document written by [`json_report`]
+pub const ANALYSIS_SCHEMA: &str = include_str!("analysis.schema.json");
+
+/// Grammar, nullable/FIRST/FOLLOW, states of the automaton built by
+/// `algo` with kernel and closure items and edges, and the table with
+/// its conflicts as one JSON document described by [`ANALYSIS_SCHEMA`]
+pub fn json_report(g: &Grammar, algo: Algo) -> String {
+    let a = Analysis::new(g, algo);
+    let chr = |c: &char| json::string(&Sym::Normal(*c).to_string());
+    let syms = |x: &[Sym]| json::array(x.iter().map(sym));
+
+    let rules = g.rules().iter().enumerate().map(|(i, r)| {
+        json::object([
+            ("index", i.to_string()),
+            ("left", chr(&r.left())),
+            ("right", json::array(r.right().iter().map(chr))),
+            ("text", json::string(&r.to_string())),
+        ])
+    });
+    let grammar = json::object([
+        ("start", chr(&g.rules()[0].left())),
+        ("terminals", json::array(sorted(g.terms()).iter().map(chr))),
+        (
+            "non_terminals",
+            json::array(sorted(g.non_terms()).iter().map(chr)),
+        ),
+        ("rules", json::array(rules)),
+    ]);
+
+    let sets = a.sets.iter().map(|s| {
+        json::object([
+            ("symbol", chr(&s.symbol)),
+            ("nullable", s.nullable.to_string()),
+            ("first", syms(&s.first)),
+            ("follow", syms(&s.follow)),
+        ])
+    });
+
+    let states = a.states.iter().enumerate().map(|(i, s)| {
+        let edges = s
+            .edges
+            .iter()
+            .map(|(c, t)| json::object([("symbol", chr(c)), ("target", t.to_string())]));
+        json::object([
+            ("index", i.to_string()),
+            (
+                "symbol",
+                s.from.as_ref().map(chr).unwrap_or("null".to_string()),
+            ),
+            ("kernel", items(&s.kernel)),
+            ("closure", items(&s.closure)),
+            ("edges", json::array(edges)),
+        ])
+    });
+
+    let table = a.table.as_ref();
+    let rows = (0..table.states()).map(|s| {
+        let actions: Vec<(String, String)> = lookahead(g)
+            .into_iter()
+            .filter_map(|x| {
+                let all = table.get_actions(s, x);
+                if all.is_empty() {
+                    return None;
+                }
+                let target = match x {
+                    Sym::Normal(c) => table.get_goto(s, c),
+                    Sym::Eps => None,
+                };
+                let all = all.iter().map(|y| action_json(y, target));
+                Some((key(&x), json::array(all)))
+            })
+            .collect();
+        let goto: Vec<(String, String)> = sorted(g.non_terms())
+            .into_iter()
+            .filter_map(|c| {
+                Some((
+                    Sym::Normal(c).to_string(),
+                    table.get_goto(s, c)?.to_string(),
+                ))
+            })
+            .collect();
+        json::object([
+            ("index", s.to_string()),
+            (
+                "actions",
+                json::object(actions.iter().map(|(k, v)| (k.as_str(), v.clone()))),
+            ),
+            (
+                "goto",
+                json::object(goto.iter().map(|(k, v)| (k.as_str(), v.clone()))),
+            ),
+        ])
+    });
+    // symbols grouped by equal actions can shift to different states
+    let conflicts = a.conflicts.iter().flat_map(|c| {
+        c.syms.iter().map(move |x| {
+            let target = match x {
+                Sym::Normal(ch) => table.get_goto(c.state, *ch),
+                Sym::Eps => None,
+            };
+            let actions = c.actions.iter().map(|y| action_json(y, target));
+            json::object([
+                ("state", c.state.to_string()),
+                ("symbol", sym(x)),
+                ("actions", json::array(actions)),
+            ])
+        })
+    });
+    let table_json = json::object([
+        ("name", json::string(table.name())),
+        ("states", json::array(rows)),
+        ("conflicts", json::array(conflicts)),
+    ]);
+
+    let stats: Vec<(String, String)> = a
+        .stats()
+        .into_iter()
+        .map(|(k, v)| (k.replace(['-', ' ', '/'], "_"), v.to_string()))
+        .collect();
+
+    json::object([
+        ("format", json::string(FORMAT)),
+        ("version", VERSION.to_string()),
+        ("algorithm", json::string(&algo.to_string())),
+        ("grammar", grammar),
+        ("sets", json::array(sets)),
+        ("automaton", json::object([("states", json::array(states))])),
+        ("table", table_json),
+        (
+            "stats",
+            json::object(stats.iter().map(|(k, v)| (k.as_str(), v.clone()))),
+        ),
+    ]) + "\n"
+}
+
+// end of input is empty string
+fn key(x: &Sym) -> String {
+    match x {
+        Sym::Normal(c) => Sym::Normal(*c).to_string(),
+        Sym::Eps => String::new(),
+    }
+}
+
+fn sym(x: &Sym) -> String {
+    json::string(&key(x))
+}
+
+// LR(1) items with the same rule and dot are merged, their lookaheads
+// are collected in one array
+fn items(all: &[Item]) -> String {
+    let mut merged: Vec<(&Item, Vec<Sym>)> = vec![];
+    for item in all {
+        match merged
+            .iter_mut()
+            .find(|(x, _)| x.rule == item.rule && x.dot == item.dot)
+        {
+            Some((_, lookahead)) => lookahead.extend(item.lookahead),
+            None => merged.push((item, item.lookahead.into_iter().collect())),
+        }
+    }
+    json::array(merged.into_iter().map(|(item, lookahead)| {
+        json::object([
+            ("rule", item.rule.to_string()),
+            ("dot", item.dot.to_string()),
+            (
+                "lookahead",
+                match item.lookahead {
+                    Some(_) => json::array(sorted_syms(lookahead).iter().map(sym)),
+                    None => "null".to_string(),
+                },
+            ),
+            ("text", json::string(&item.core)),
+        ])
+    }))
+}
+
+// shift target is the transition on the symbol, None when LR(0) table
+// shifts without one
+fn action_json(action: &Action, target: Option<usize>) -> String {
+    match action {
+        Action::Shift => json::object([
+            ("type", json::string("shift")),
+            (
+                "state",
+                target.map(|x| x.to_string()).unwrap_or("null".to_string()),
+            ),
+        ]),
+        Action::Reduction(r) => {
+            json::object([("type", json::string("reduce")), ("rule", r.to_string())])
+        }
+        Action::Accept => json::object([("type", json::string("accept"))]),
+        Action::Error | Action::Empty => unreachable!("not returned by get_actions"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        json::{parse, Value},
+        testing::tables,
+    };
+
+    use super::*;
+
+    // subset of JSON Schema used by ANALYSIS_SCHEMA
+    fn validate(schema: &Value, value: &Value, root: &Value, path: &str) -> Result<(), String> {
+        if let Some(r) = schema.get("$ref").and_then(Value::as_str) {
+            let name = r.trim_start_matches("#/$defs/");
+            let def = root.get("$defs").and_then(|x| x.get(name)).unwrap();
+            return validate(def, value, root, path);
+        }
+        if let Some(any) = schema.get("anyOf").and_then(Value::as_array) {
+            return match any.iter().any(|s| validate(s, value, root, path).is_ok()) {
+                true => Ok(()),
+                false => Err(format!("{} matches no alternative", path)),
+            };
+        }
+        if let Some(all) = schema.get("enum").and_then(Value::as_array) {
+            if !all.contains(value) {
+                return Err(format!("{} is not in enum", path));
+            }
+        }
+        let kind = match value {
+            Value::Null => "null",
+            Value::Bool(_) => "boolean",
+            Value::Number(_) => "integer",
+            Value::String(_) => "string",
+            Value::Array(_) => "array",
+            Value::Object(_) => "object",
+        };
+        if let Some(t) = schema.get("type").and_then(Value::as_str) {
+            if t != kind || (kind == "integer" && value.as_u64().is_none()) {
+                return Err(format!("{} is not {}", path, t));
+            }
+        }
+        if let (Some(items), Some(all)) = (schema.get("items"), value.as_array()) {
+            for (i, x) in all.iter().enumerate() {
+                validate(items, x, root, &format!("{}[{}]", path, i))?;
+            }
+        }
+        if let Some(fields) = value.as_object() {
+            for key in schema
+                .get("required")
+                .and_then(Value::as_array)
+                .unwrap_or(&[])
+            {
+                if value.get(key.as_str().unwrap()).is_none() {
+                    return Err(format!("{} misses {:?}", path, key));
+                }
+            }
+            for (k, v) in fields {
+                let path = format!("{}.{}", path, k);
+                match schema.get("properties").and_then(|x| x.get(k)) {
+                    Some(s) => validate(s, v, root, &path)?,
+                    None => match schema.get("additionalProperties") {
+                        Some(Value::Bool(false)) => return Err(format!("{} is unknown", path)),
+                        Some(s) => validate(s, v, root, &path)?,
+                        None => (),
+                    },
+                }
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn schema() {
+        let schema = parse(ANALYSIS_SCHEMA).unwrap();
+        for (name, algo, table) in tables() {
+            let doc = parse(&json_report(table.grammar(), algo)).unwrap();
+            if let Err(e) = validate(&schema, &doc, &schema, "") {
+                panic!("{} : {}", name, e);
+            }
+            let states = doc.get("automaton").unwrap().get("states").unwrap();
+            let rows = doc.get("table").unwrap().get("states").unwrap();
+            assert_eq!(
+                states.as_array().unwrap().len(),
+                rows.as_array().unwrap().len()
+            );
+        }
+        let broken = parse("{\"format\":\"lr0_gen analysis\",\"version\":2}").unwrap();
+        assert!(validate(&schema, &broken, &schema, "").is_err());
+    }
+
+    #[test]
+    fn content() {
+        let g = Grammar::from_text(include_str!("../../examples/assign.gram")).unwrap();
+        let doc = parse(&json_report(&g, Algo::SLR1)).unwrap();
+        let rule = &doc
+            .get("grammar")
+            .unwrap()
+            .get("rules")
+            .unwrap()
+            .as_array()
+            .unwrap()[1];
+        assert_eq!(rule.get("text").unwrap().as_str(), Some("A -> L = R"));
+        let state = &doc
+            .get("table")
+            .unwrap()
+            .get("states")
+            .unwrap()
+            .as_array()
+            .unwrap()[6];
+        let all = state
+            .get("actions")
+            .unwrap()
+            .get("=")
+            .unwrap()
+            .as_array()
+            .unwrap();
+        assert_eq!(all[0].get("type").unwrap().as_str(), Some("shift"));
+        assert!(all[0].get("state").unwrap().as_u64().is_some());
+        assert_eq!(all[1].get("rule").unwrap().as_u64(), Some(5));
+        let conflicts = doc
+            .get("table")
+            .unwrap()
+            .get("conflicts")
+            .unwrap()
+            .as_array()
+            .unwrap();
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].get("state").unwrap().as_u64(), Some(6));
+        assert_eq!(conflicts[0].get("symbol").unwrap().as_str(), Some("="));
+        let stats = doc.get("stats").unwrap();
+        assert_eq!(
+            stats.get("shift_reduce_conflicts").unwrap().as_u64(),
+            Some(1)
+        );
+
+        // LR(1) items of one core share the entry
+        let doc = parse(&json_report(&g, Algo::LR1)).unwrap();
+        let state = &doc
+            .get("automaton")
+            .unwrap()
+            .get("states")
+            .unwrap()
+            .as_array()
+            .unwrap()[0];
+        let closure = state.get("closure").unwrap().as_array().unwrap();
+        let item = closure
+            .iter()
+            .find(|x| x.get("text").unwrap().as_str() == Some("L -> . a"))
+            .unwrap();
+        let lookahead: Vec<&str> = item
+            .get("lookahead")
+            .unwrap()
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|x| x.as_str().unwrap())
+            .collect();
+        assert_eq!(lookahead, ["=", ""]);
+        assert_eq!(item.get("rule").unwrap().as_u64(), Some(4));
+        assert_eq!(item.get("dot").unwrap().as_u64(), Some(0));
+
+        // c and d conflict the same way but shift to different states
+        let g = Grammar::from_text("S A X\nc d\nS->A\nA->Xc\nA->Xd\nA->c\nA->d\nX->\n").unwrap();
+        let doc = parse(&json_report(&g, Algo::SLR1)).unwrap();
+        let conflicts = doc
+            .get("table")
+            .unwrap()
+            .get("conflicts")
+            .unwrap()
+            .as_array()
+            .unwrap();
+        let shifts: Vec<(&str, u64)> = conflicts
+            .iter()
+            .map(|x| {
+                let shift = &x.get("actions").unwrap().as_array().unwrap()[0];
+                assert_eq!(shift.get("type").unwrap().as_str(), Some("shift"));
+                let target = shift.get("state").unwrap().as_u64().unwrap();
+                (x.get("symbol").unwrap().as_str().unwrap(), target)
+            })
+            .collect();
+        assert_eq!(shifts, [("c", 5), ("d", 6)]);
+    }
+}
@@ -0,0 +1,202 @@
+use crate::{
+    grammar::{Grammar, Sym},
+    report::Analysis,
+    table::{
+        algo::Algo,
+        lrtable::Action,
+        render::{html, Html, Renderer},
+    },
+};
+
+const STYLE: &str = "body { font-family: sans-serif; margin: 2em; color: #222; }
+nav a { margin-right: 1em; }
+table { border-collapse: collapse; margin: 1em 0; }
+th, td { border: 1px solid #bbb; padding: 0.2em 0.6em; text-align: center; }
+td.left, th.left { text-align: left; }
+td.conflict { background: #f8d7da; font-weight: bold; }
+.state { border: 1px solid #ccc; padding: 0.5em 1em; margin: 1em 0; }
+.state.conflict { border-color: #c00; }
+.closure { color: #666; }
+.table-wrap { overflow-x: auto; }
+code, pre { font-family: monospace; }
+";
+
+/// Single offline HTML page with everything about the grammar and its
+/// automaton built by `algo`: numbered rules, nullable/FIRST/FOLLOW,
+/// statistics, conflicts, parse table and every state with items and
+/// transitions. Style is inlined, there are no scripts or links out.
+pub fn html_report(g: &Grammar, algo: Algo) -> String {
+    let a = Analysis::new(g, algo);
+    let name = a.table.name().to_string();
+    let sym = |x: &Sym| html(&x.to_string());
+    let mut res = format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <title>{} report</title>\n<style>\n{}</style>\n</head>\n<body>\n",
+        html(&name),
+        STYLE
+    );
+    res.push_str(&format!("<h1>{} report</h1>\n", html(&name)));
+    res.push_str(&format!(
+        "<p>Generated by lr0_gen with {} construction.</p>\n",
+        a.algo
+    ));
+    res.push_str(
+        "<nav><a href=\"#stats\">Statistics</a><a href=\"#rules\">Rules</a>\
+         <a href=\"#sets\">FIRST and FOLLOW</a><a href=\"#conflicts\">Conflicts</a>\
+         <a href=\"#table\">Table</a><a href=\"#states\">States</a></nav>\n",
+    );
+
+    res.push_str("<h2 id=\"stats\">Statistics</h2>\n<table>\n");
+    for (key, value) in a.stats() {
+        res.push_str(&format!(
+            "<tr><th class=\"left\">{}</th><td>{}</td></tr>\n",
+            key, value
+        ));
+    }
+    res.push_str("</table>\n");
+
+    res.push_str(
+        "<h2 id=\"rules\">Rules</h2>\n<table>\n<tr><th>#</th><th class=\"left\">rule</th></tr>\n",
+    );
+    for (i, r) in g.rules().iter().enumerate() {
+        res.push_str(&format!(
+            "<tr id=\"rule-{}\"><td>{}</td><td class=\"left\"><code>{}</code></td></tr>\n",
+            i,
+            i,
+            html(&r.to_string())
+        ));
+    }
+    res.push_str("</table>\n");
+
+    res.push_str(
+        "<h2 id=\"sets\">Nullable, FIRST and FOLLOW</h2>\n<table>\n\
+         <tr><th>non-terminal</th><th>nullable</th><th class=\"left\">FIRST</th>\
+         <th class=\"left\">FOLLOW</th></tr>\n",
+    );
+    for s in &a.sets {
+        let first: Vec<String> = s.first.iter().map(sym).collect();
+        let follow: Vec<String> = s.follow.iter().map(sym).collect();
+        res.push_str(&format!(
+            "<tr><td>{}</td><td>{}</td><td class=\"left\">{}</td><td class=\"left\">{}</td></tr>\n",
+            sym(&Sym::Normal(s.symbol)),
+            if s.nullable { "yes" } else { "no" },
+            first.join(" "),
+            follow.join(" ")
+        ));
+    }
+    res.push_str("</table>\n");
+
+    res.push_str("<h2 id=\"conflicts\">Conflicts</h2>\n");
+    match a.conflicts.is_empty() {
+        true => res.push_str("<p>The table has no conflicts.</p>\n"),
+        false => {
+            res.push_str("<ul>\n");
+            for c in &a.conflicts {
+                let syms: Vec<String> = c.syms.iter().map(sym).collect();
+                let rules: Vec<String> = c
+                    .actions
+                    .iter()
+                    .filter_map(|x| match x {
+                        Action::Reduction(r) => Some(format!(
+                            "<a href=\"#rule-{}\">R{}</a> <code>{}</code>",
+                            r,
+                            r,
+                            html(&g.rules()[*r].to_string())
+                        )),
+                        _ => None,
+                    })
+                    .collect();
+                let actions: Vec<String> = c.actions.iter().map(|x| x.to_string()).collect();
+                res.push_str(&format!(
+                    "<li><a href=\"#state-{}\">state {}</a> on {} : {} ({})</li>\n",
+                    c.state,
+                    c.state,
+                    syms.join(" "),
+                    actions.join("/"),
+                    rules.join(", ")
+                ));
+            }
+            res.push_str("</ul>\n");
+        }
+    }
+
+    res.push_str("<h2 id=\"table\">Parse table</h2>\n<div class=\"table-wrap\">\n");
+    res.push_str(&Html.render(a.table.as_ref()));
+    res.push_str("</div>\n");
+
+    res.push_str("<h2 id=\"states\">States</h2>\n");
+    for (i, s) in a.states.iter().enumerate() {
+        let conflict = a.conflicts.iter().any(|c| c.state == i);
+        res.push_str(&format!(
+            "<div class=\"state{}\" id=\"state-{}\">\n<h3>state {}{}</h3>\n<pre>",
+            if conflict { " conflict" } else { "" },
+            i,
+            i,
+            match s.from {
+                Some(c) => format!(" (on {})", sym(&Sym::Normal(c))),
+                None => String::new(),
+            }
+        ));
+        for item in &s.kernel {
+            res.push_str(&format!("  {}\n", html(&item.text)));
+        }
+        for item in &s.closure {
+            res.push_str(&format!(
+                "<span class=\"closure\">+ {}</span>\n",
+                html(&item.text)
+            ));
+        }
+        res.push_str("</pre>\n");
+        if !s.edges.is_empty() {
+            res.push_str("<p>");
+            let edges: Vec<String> = s
+                .edges
+                .iter()
+                .map(|(c, t)| {
+                    format!(
+                        "{} &rarr; <a href=\"#state-{}\">{}</a>",
+                        sym(&Sym::Normal(*c)),
+                        t,
+                        t
+                    )
+                })
+                .collect();
+            res.push_str(&edges.join(", "));
+            res.push_str("</p>\n");
+        }
+        res.push_str("</div>\n");
+    }
+    res.push_str("</body>\n</html>\n");
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn report() {
+        let g = Grammar::from_text(include_str!("../../examples/assign.gram")).unwrap();
+        let page = html_report(&g, Algo::SLR1);
+        assert!(page.starts_with("<!DOCTYPE html>\n"));
+        assert!(page.ends_with("</html>\n"));
+        // offline, nothing is loaded from elsewhere
+        assert!(!page.contains("http") && !page.contains("<script") && !page.contains("<link"));
+        assert!(page.contains(
+            "<tr id=\"rule-5\"><td>5</td><td class=\"left\"><code>R -&gt; L</code></td></tr>"
+        ));
+        assert!(page.contains(
+            "<li><a href=\"#state-6\">state 6</a> on = : S/R5 (<a href=\"#rule-5\">R5</a>"
+        ));
+        assert!(page.contains("<th class=\"left\">shift/reduce conflicts</th><td>1</td>"));
+        assert!(
+            page.contains("<div class=\"state conflict\" id=\"state-6\">\n<h3>state 6 (on L)</h3>")
+        );
+        assert!(page.contains("<td class=\"conflict\""));
+        assert_eq!(page.matches("<div class=\"state").count(), 10);
+
+        let page = html_report(&g, Algo::LALR1);
+        assert!(page.contains("<p>The table has no conflicts.</p>"));
+        assert!(page.contains("<tr><td>L</td><td>no</td><td class=\"left\">* a</td><td class=\"left\">= eps</td></tr>"));
+    }
+}
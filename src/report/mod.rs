@@ -0,0 +1,104 @@
+pub mod bison;
+pub mod export;
+pub mod html;
+
+use crate::{
+    grammar::{Grammar, Sym},
+    graph::lrgraph::StateItems,
+    table::{
+        algo::{conflicts, Algo, Conflict},
+        lrtable::{lookahead, sorted, Action, Table},
+    },
+};
+
+// nullable, FIRST and FOLLOW of one non-terminal
+pub(crate) struct Sets {
+    pub(crate) symbol: char,
+    pub(crate) nullable: bool,
+    pub(crate) first: Vec<Sym>,
+    pub(crate) follow: Vec<Sym>,
+}
+
+// everything reports show about the grammar and its automaton
+pub(crate) struct Analysis {
+    pub(crate) gramm: Grammar,
+    pub(crate) algo: Algo,
+    pub(crate) table: Box<dyn Table>,
+    pub(crate) states: Vec<StateItems>,
+    pub(crate) conflicts: Vec<Conflict>,
+    pub(crate) sets: Vec<Sets>,
+}
+
+impl Analysis {
+    pub(crate) fn new(g: &Grammar, algo: Algo) -> Self {
+        let table = algo.build(g);
+        let sets = sorted(g.non_terms())
+            .into_iter()
+            .map(|n| {
+                let mut first = g.first_from(&[n]);
+                let nullable = first.remove(&Sym::Eps);
+                Sets {
+                    symbol: n,
+                    nullable,
+                    first: sorted_syms(first),
+                    follow: sorted_syms(g.follow(n).iter().copied()),
+                }
+            })
+            .collect();
+        Self {
+            gramm: g.clone(),
+            algo,
+            states: algo.state_items(g),
+            conflicts: conflicts(table.as_ref()),
+            table,
+            sets,
+        }
+    }
+
+    // named counts, same order in every report
+    pub(crate) fn stats(&self) -> Vec<(&'static str, usize)> {
+        let lookahead = lookahead(&self.gramm);
+        let mut actions = 0;
+        for s in 0..self.table.states() {
+            actions += lookahead
+                .iter()
+                .filter(|x| !self.table.get_actions(s, **x).is_empty())
+                .count();
+        }
+        let transitions: usize = self.states.iter().map(|x| x.edges.len()).sum();
+        let gotos: usize = self
+            .states
+            .iter()
+            .flat_map(|x| &x.edges)
+            .filter(|(c, _)| self.gramm.is_non_term(c))
+            .count();
+        let kind = |shift: bool| {
+            self.conflicts
+                .iter()
+                .filter(|c| c.actions.contains(&Action::Shift) == shift)
+                .map(|c| c.syms.len())
+                .sum()
+        };
+        vec![
+            ("rules", self.gramm.rules().len()),
+            ("terminals", self.gramm.terms().len()),
+            ("non-terminals", self.gramm.non_terms().len()),
+            ("states", self.states.len()),
+            ("transitions", transitions),
+            ("action entries", actions),
+            ("goto entries", gotos),
+            ("shift/reduce conflicts", kind(true)),
+            ("reduce/reduce conflicts", kind(false)),
+        ]
+    }
+}
+
+// terminals sorted, end of input last
+pub(crate) fn sorted_syms(set: impl IntoIterator<Item = Sym>) -> Vec<Sym> {
+    let mut res: Vec<Sym> = set.into_iter().collect();
+    res.sort_by_key(|x| match x {
+        Sym::Normal(c) => (0, *c),
+        Sym::Eps => (1, ' '),
+    });
+    res
+}
@@ -0,0 +1,118 @@
+use std::collections::HashMap;
+
+/// Hooks called by StackAutomata, values are kept on stack
+/// parallel to the state stack, reduction gets values of
+/// the right side of the rule in order
+pub trait Semantics {
+    type Value;
+    fn on_shift(&mut self, sym: char, offset: usize) -> Self::Value;
+    fn on_reduce(&mut self, rule: usize, left: char, values: Vec<Self::Value>) -> Self::Value;
+}
+
+type ShiftAction<'f, V> = Box<dyn FnMut(char, usize) -> V + 'f>;
+type ReduceAction<'f, V> = Box<dyn FnMut(Vec<V>) -> V + 'f>;
+
+/// Semantics from closures per rule index, rule without closure
+/// passes value of its only symbol or default value
+pub struct Actions<'f, V> {
+    shift: ShiftAction<'f, V>,
+    reduce: HashMap<usize, ReduceAction<'f, V>>,
+}
+
+impl<'f, V> Actions<'f, V> {
+    pub fn new<F>(shift: F) -> Self
+    where
+        F: FnMut(char, usize) -> V + 'f,
+    {
+        Self {
+            shift: Box::new(shift),
+            reduce: HashMap::new(),
+        }
+    }
+
+    pub fn on<F>(mut self, rule: usize, action: F) -> Self
+    where
+        F: FnMut(Vec<V>) -> V + 'f,
+    {
+        self.reduce.insert(rule, Box::new(action));
+        self
+    }
+}
+
+impl<V: Default> Semantics for Actions<'_, V> {
+    type Value = V;
+
+    fn on_shift(&mut self, sym: char, offset: usize) -> V {
+        (self.shift)(sym, offset)
+    }
+
+    fn on_reduce(&mut self, rule: usize, _: char, mut values: Vec<V>) -> V {
+        match self.reduce.get_mut(&rule) {
+            Some(action) => action(values),
+            None if values.len() == 1 => values.pop().unwrap(),
+            None => V::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        grammar::Grammar,
+        graph::{lalrgraph::LALRGraph, lalrnode::LALRNode, lrgraph::LRGraph, lrnode::LRNode},
+        stackautomata::StackAutomata,
+        table::lr1table::LR1Table,
+    };
+
+    use super::*;
+
+    // Evaluator for arithmetic of examples/example.gram, `a` is variable
+    fn calculator<'f>(a: i64) -> Actions<'f, i64> {
+        Actions::new(move |c, _| if c == 'a' { a } else { 0 })
+            .on(1, |v| v[0] + v[2])
+            .on(3, |v| v[0] * v[2])
+            .on(6, |v| v[1])
+    }
+
+    fn eval(g: &Grammar, input: &str, sem: Actions<'_, i64>) -> Option<i64> {
+        let mut graph = LALRGraph::new();
+        graph.construct(LALRNode::default(g));
+        let table = LR1Table::new(graph, g);
+        StackAutomata::with_semantics(&table, input, sem).run().ok()
+    }
+
+    #[test]
+    fn arithmetic() {
+        let g = Grammar::from_text(include_str!("../examples/example.gram")).unwrap();
+        assert_eq!(eval(&g, "a+a*a", calculator(3)), Some(12));
+        assert_eq!(eval(&g, "(a+a)*a", calculator(3)), Some(18));
+        assert_eq!(eval(&g, "a*(a+a*(a))+a", calculator(2)), Some(14));
+        assert_eq!(eval(&g, "a+*a", calculator(2)), None);
+    }
+
+    #[test]
+    fn numbers() {
+        // value with number of its digits
+        let g = Grammar::from_text(include_str!("../examples/better.gram")).unwrap();
+        let mut sem = Actions::new(|c, _| match c.to_digit(10) {
+            Some(d) => (d as i64, 1),
+            None => (7, 1),
+        })
+        .on(1, |v| (v[0].0 + v[2].0, 0))
+        .on(3, |v| (v[0].0 * v[2].0, 0))
+        .on(7, |v| v[1]);
+        for r in 8..g.rules.len() {
+            if g.rules[r].right.len() == 2 {
+                sem = sem.on(r, |v| (v[0].0 * 10_i64.pow(v[1].1) + v[1].0, v[1].1 + 1));
+            }
+        }
+
+        let mut graph = LALRGraph::new();
+        graph.construct(LALRNode::default(&g));
+        let table = LR1Table::new(graph, &g);
+        let res = StackAutomata::with_semantics(&table, "12+3*(a+105)", sem)
+            .run()
+            .ok();
+        assert_eq!(res.map(|x| x.0), Some(12 + 3 * (7 + 105)));
+    }
+}
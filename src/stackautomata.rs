@@ -1,110 +1,302 @@
 use std::fmt::Display;
 
 use crate::{
-    grammar::{Grammar, Rule, Sym}, table::lrtable::{Action, Table},
+    grammar::{Grammar, Rule, Sym, ERROR},
+    parseerror::ParseError,
+    parsetree::TreeBuilder,
+    repair::{Repair, RepairSearch},
+    semantic::Semantics,
+    table::lrtable::{lookahead, Action, ClassicAction, Table},
+    trace::{Trace, TraceAction, TraceStep},
 };
 
-pub(crate) struct StackAutomata<'a> {
+/// Deterministic driver of a table, conflicting cells are errors
+pub struct StackAutomata<'a, S: Semantics = TreeBuilder> {
     stack: Vec<usize>,
     input: Vec<char>,
     result: Vec<usize>,
+    // values for symbols on stack (without bottom state)
+    values: Vec<S::Value>,
+    value: Option<S::Value>,
+    sem: S,
     place: usize,
     gramm: &'a Grammar,
     table: &'a dyn Table,
+    trace: Option<Trace>,
 }
 
-impl Display for StackAutomata<'_> {
+impl<S: Semantics> Display for StackAutomata<'_, S> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "stack : ",)?;
         for s in self.stack.iter() {
             write!(f, "{} ", s)?;
         }
-        writeln!(f, "")?;
+        writeln!(f)?;
 
         write!(f, "input : ",)?;
         for s in self.input.iter().skip(self.place) {
             write!(f, "{} ", s)?;
         }
-        writeln!(f, "")?;
+        writeln!(f)?;
 
         write!(f, "result : ",)?;
         for s in self.result.iter() {
             write!(f, "{} ", s)?;
         }
-        writeln!(f, "")?;
+        writeln!(f)?;
 
         Ok(())
     }
 }
 
 impl<'a> StackAutomata<'a> {
-    pub(crate) fn new(table: &'a dyn Table, input: &'a str, gramm: &'a Grammar) -> Self {
+    pub fn new(table: &'a dyn Table, input: &str) -> Self {
+        Self::with_semantics(table, input, TreeBuilder)
+    }
+}
+
+impl<'a, S: Semantics> StackAutomata<'a, S> {
+    pub fn with_semantics(table: &'a dyn Table, input: &str, sem: S) -> Self {
         Self {
             stack: vec![0],
             table,
             input: input.chars().collect(),
             result: vec![],
+            values: vec![],
+            value: None,
+            sem,
             place: 0,
-            gramm,
+            gramm: table.grammar(),
+            trace: None,
         }
     }
 
-    fn compare_stack(&mut self, rule: &Rule) -> Option<char> {
-        if rule.right.len() > self.stack.len() {
-            return None;
-        }
-        for c in rule.right.iter().rev() {
-            let state = self.stack.pop()?;
-            let tmp = self.table.get_char(state)?;
-            if tmp != *c {
+    // every following step will be recorded
+    pub fn enable_trace(&mut self) {
+        self.trace = Some(Trace::default());
+    }
+
+    pub fn trace(&self) -> Option<&Trace> {
+        self.trace.as_ref()
+    }
+
+    // state under the right side of rule on top of stack, the stack is
+    // left untouched so errors are reported from the state before reduction
+    fn compare_stack(&self, rule: &Rule) -> Option<usize> {
+        let below = self.stack.len().checked_sub(rule.right.len() + 1)?;
+        for (state, c) in self.stack[below + 1..].iter().zip(&rule.right) {
+            if self.table.get_char(*state)? != *c {
                 return None;
             }
         }
-        Some(rule.left)
+        Some(self.stack[below])
     }
 
-    pub(crate) fn run(&mut self) -> Option<()> {
+    // gives values of reduced symbols to semantics
+    fn reduce_value(&mut self, x: usize, rule: &Rule) -> S::Value {
+        let values = self
+            .values
+            .split_off(self.values.len().saturating_sub(rule.right.len()));
+        self.sem.on_reduce(x, rule.left, values)
+    }
+
+    // value of the starting rule on accept
+    pub fn run(&mut self) -> Result<S::Value, ParseError> {
         loop {
             let a = self.step()?;
             if a == Action::Accept {
                 break;
             }
         }
-        Some(())
+        self.value
+            .take()
+            .ok_or_else(|| self.error(self.top().unwrap_or(0)))
+    }
+
+    // Runs with yacc like recovery using rules with `error` terminal,
+    // returns value (if the input was recovered) and all errors found
+    pub fn run_recovering(&mut self) -> (Option<S::Value>, Vec<ParseError>) {
+        let mut errors = vec![];
+        // number of terminals to shift before next error is reported
+        let mut quiet = 0;
+        loop {
+            match self.step() {
+                Ok(Action::Accept) => return (self.value.take(), errors),
+                Ok(Action::Shift) => quiet = usize::saturating_sub(quiet, 1),
+                Ok(_) => (),
+                Err(e) => {
+                    if quiet == 0 {
+                        errors.push(e);
+                    }
+                    if !self.recover(&mut quiet) {
+                        return (None, errors);
+                    }
+                }
+            }
+        }
+    }
+
+    // Runs with automatic repair of input, on every error the cheapest
+    // edits after which next `check` (at least one) symbols parse are applied
+    pub fn run_repairing(&mut self, check: usize) -> (Result<S::Value, ParseError>, Vec<Repair>) {
+        let search = RepairSearch::new(self.table, check);
+        let mut repairs = vec![];
+        // inserted minus deleted symbols, to get original offsets
+        let mut delta: isize = 0;
+        // original offset of the last repair, error at the same place
+        // again means the repair did not move the parse forward
+        let mut last = None;
+        loop {
+            match self.step() {
+                Ok(Action::Accept) => {
+                    let state = self.top().unwrap_or(0);
+                    return (self.value.take().ok_or_else(|| self.error(state)), repairs);
+                }
+                Ok(_) => (),
+                Err(e) => {
+                    let offset = (self.place as isize - delta) as usize;
+                    if last == Some(offset) {
+                        return (Err(e), repairs);
+                    }
+                    last = Some(offset);
+                    let found = match search.find(&self.stack, &self.input, self.place, offset) {
+                        Some(f) => f,
+                        None => return (Err(e), repairs),
+                    };
+                    delta += found.emitted.len() as isize - (found.place - self.place) as isize;
+                    self.input.splice(self.place..found.place, found.emitted);
+                    repairs.extend(found.repairs);
+                }
+            }
+        }
+    }
+
+    // pops states until one can shift error and shifts it, when
+    // nothing was shifted since last recovery the lookahead is dropped
+    fn recover(&mut self, quiet: &mut usize) -> bool {
+        if *quiet == 3 {
+            return self.next_char().is_some();
+        }
+        *quiet = 3;
+        loop {
+            let state = self.top().unwrap_or(0);
+            if self.table.get_action(state, Sym::Normal(ERROR)) == Some(Action::Shift) {
+                if let Some(target) = self.table.get_goto(state, ERROR) {
+                    self.stack.push(target);
+                    let value = self.sem.on_shift(ERROR, self.place);
+                    self.values.push(value);
+                    return true;
+                }
+            }
+            if self.stack.len() <= 1 {
+                return false;
+            }
+            self.stack.pop();
+            self.values.pop();
+        }
     }
 
-    pub(crate) fn step(&mut self) -> Option<Action> {
-        let top_stack = self.top()?;
-        let nchar = match self.peek_char() {
+    pub fn step(&mut self) -> Result<Action, ParseError> {
+        let state = self.top().unwrap_or(0);
+        let before = self.trace.as_ref().map(|_| {
+            let symbols = self.stack[1..]
+                .iter()
+                .filter_map(|x| self.table.get_char(*x))
+                .collect();
+            (
+                self.stack.clone(),
+                symbols,
+                self.input[self.place..].to_vec(),
+            )
+        });
+        let res = match self.do_step(state) {
+            Some(a) => Ok(a),
+            None => Err(self.error(state)),
+        };
+        if let Some((states, symbols, input)) = before {
+            let top = self.top().unwrap_or(0);
+            let action = match res {
+                Ok(Action::Shift) => TraceAction::Shift(top),
+                Ok(Action::Reduction(r)) => TraceAction::Reduce {
+                    rule: r,
+                    text: self.gramm.rules[r].to_string(),
+                    goto: top,
+                },
+                Ok(Action::Accept) => TraceAction::Accept,
+                _ => TraceAction::Error,
+            };
+            let step = TraceStep {
+                states,
+                symbols,
+                input,
+                action,
+            };
+            self.trace.as_mut().unwrap().push(step);
+        }
+        res
+    }
+
+    fn lookahead(&self) -> Sym {
+        match self.peek_char() {
             Some(x) => Sym::Normal(x),
             None => Sym::Eps,
-        };
-        let action = self.table.get_action(top_stack, nchar)?;
+        }
+    }
+
+    fn error(&self, state: usize) -> ParseError {
+        let expected = lookahead(self.gramm)
+            .into_iter()
+            // error terminal is never in the input
+            .filter(|x| *x != Sym::Normal(ERROR))
+            // same actions as do_step, LR(0) shift without transition is none
+            .filter(|x| self.table.get_classic(state, *x).is_some())
+            .collect();
+        ParseError::new(&self.input, self.place, self.lookahead(), state, expected)
+    }
+
+    fn do_step(&mut self, top_stack: usize) -> Option<Action> {
+        let action = self.table.get_classic(top_stack, self.lookahead())?;
         match action {
-            Action::Shift => {
+            ClassicAction::Shift(target) => {
+                let offset = self.place;
                 let c = self.next_char()?;
-                self.stack.push(self.get_goto(c)?);
+                self.stack.push(target);
+                let value = self.sem.on_shift(c, offset);
+                self.values.push(value);
                 Some(Action::Shift)
             }
-            Action::Accept => {
+            ClassicAction::Accept => {
                 let x = 0;
                 let rule = self.gramm.rules.get(x)?;
                 self.compare_stack(rule)?;
+                self.stack.truncate(self.stack.len() - rule.right.len());
+                self.value = Some(self.reduce_value(x, rule));
                 self.result.push(x);
                 Some(Action::Accept)
             }
-            Action::Reduction(x) => {
-                let x = x.clone();
+            ClassicAction::Reduce(x) => {
                 let rule = self.gramm.rules.get(x)?;
-                let c = self.compare_stack(rule)?;
-                self.stack.push(self.get_goto(c)?);
+                let below = self.compare_stack(rule)?;
+                let target = self.table.get_goto(below, rule.left)?;
+                self.stack.truncate(self.stack.len() - rule.right.len());
+                self.stack.push(target);
+                let value = self.reduce_value(x, rule);
+                self.values.push(value);
                 self.result.push(x);
                 Some(Action::Reduction(x))
             }
-            Action::Error | Action::Empty => return None,
         }
     }
 
+    pub fn result(&self) -> &Vec<usize> {
+        &self.result
+    }
+
+    // whole input was read
+    pub fn consumed(&self) -> bool {
+        self.place >= self.input.len()
+    }
+
     pub(crate) fn top(&self) -> Option<usize> {
         self.stack.last().copied()
     }
@@ -115,16 +307,257 @@ impl<'a> StackAutomata<'a> {
         Some(res)
     }
 
-    pub(crate) fn peek_char(&mut self) -> Option<char> {
+    pub(crate) fn peek_char(&self) -> Option<char> {
         if self.place < self.input.len() {
             self.input.get(self.place).copied()
         } else {
             None
         }
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        graph::{
+            lr1graph::LR1Node,
+            lrgraph::{LR1Graph, LRGraph},
+            lrnode::LRNode,
+        },
+        table::lr1table::LR1Table,
+    };
+
+    use crate::parsetree::ParseTree;
+
+    use super::*;
+
+    fn parse(gramm: &Grammar, input: &str) -> Option<ParseTree> {
+        let mut graph = LR1Graph::new();
+        graph.construct(LR1Node::default(gramm));
+        let table = LR1Table::new(graph, gramm);
+        StackAutomata::new(&table, input).run().ok()
+    }
+
+    #[test]
+    fn tree() {
+        let g = Grammar::from_text(include_str!("../examples/example.gram")).unwrap();
+        let tree = parse(&g, "a+(a)").unwrap();
+        assert_eq!(
+            tree.sexp(),
+            "(S (E (E (T (F a))) + (T (F ( (E (T (F a))) )))))"
+        );
+        assert_eq!(tree.text(), "a+(a)");
+        assert_eq!(tree.rule(), Some(0));
+        let leaves: Vec<(char, usize)> = tree
+            .preorder()
+            .filter_map(|(_, x)| match x {
+                ParseTree::Leaf { sym, offset } => Some((*sym, *offset)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            leaves,
+            vec![('a', 0), ('+', 1), ('(', 2), ('a', 3), (')', 4)]
+        );
+        assert!(parse(&g, "a+").is_none());
+    }
+
+    #[test]
+    fn shared_table() {
+        // table outlives the grammar it was built from
+        let table: std::sync::Arc<dyn Table> = {
+            let g = Grammar::from_text(include_str!("../examples/example.gram")).unwrap();
+            crate::Algo::LALR1.build(&g).into()
+        };
+        let handles: Vec<_> = ["a+a", "(a)*a", "a+", "((a+a)*(a))"]
+            .iter()
+            .map(|input| {
+                let table = table.clone();
+                std::thread::spawn(move || StackAutomata::new(table.as_ref(), input).run().ok())
+            })
+            .collect();
+        let res: Vec<Option<String>> = handles
+            .into_iter()
+            .map(|x| x.join().unwrap().map(|t| t.text()))
+            .collect();
+        assert_eq!(
+            res,
+            vec![
+                Some("a+a".to_string()),
+                Some("(a)*a".to_string()),
+                None,
+                Some("((a+a)*(a))".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn recovery() {
+        let g = Grammar::from_text(include_str!("../examples/recovery.gram")).unwrap();
+        let mut graph = LR1Graph::new();
+        graph.construct(LR1Node::default(&g));
+        let table = LR1Table::new(graph, &g);
+
+        let mut autom = StackAutomata::new(&table, "a=a;a==a;a=a;a=;");
+        let (tree, errors) = autom.run_recovering();
+        assert_eq!(
+            errors.iter().map(|x| x.offset).collect::<Vec<_>>(),
+            vec![6, 15]
+        );
+        assert_eq!(
+            tree.unwrap().sexp(),
+            "(S (L (L (L (L (T a = a ;)) (T error ;)) (T a = a ;)) (T error ;)))"
+        );
+
+        let mut autom = StackAutomata::new(&table, "a=a;a=a");
+        let (tree, errors) = autom.run_recovering();
+        assert!(tree.is_none());
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].found, Sym::Eps);
+
+        let (tree, errors) = StackAutomata::new(&table, "a=a;").run_recovering();
+        assert!(tree.is_some() && errors.is_empty());
+
+        // error terminal is not something the input can contain
+        let err = StackAutomata::new(&table, "a=a;;").run().unwrap_err();
+        assert_eq!(err.expected, vec![Sym::Normal('a'), Sym::Eps]);
+    }
+
+    #[test]
+    fn trace() {
+        let g = Grammar::from_text(include_str!("../examples/simple.gram")).unwrap();
+        let mut graph = LR1Graph::new();
+        graph.construct(LR1Node::default(&g));
+        let table = LR1Table::new(graph, &g);
+        let mut autom = StackAutomata::new(&table, "ab");
+        autom.enable_trace();
+        autom.run().unwrap();
+        let trace = autom.trace().unwrap();
+
+        let actions: Vec<String> = trace.steps.iter().map(|x| x.action.to_string()).collect();
+        let a = table.get_goto(0, 'a').unwrap();
+        let b = table
+            .get_goto(table.get_goto(a, 'A').unwrap(), 'b')
+            .unwrap();
+        assert_eq!(actions[0], format!("shift {}", a));
+        assert!(actions[1].starts_with("reduce 2 (A -> eps), goto "));
+        assert_eq!(actions[2], format!("shift {}", b));
+        assert!(actions[3].starts_with("reduce 1 (A -> a A b), goto "));
+        assert_eq!(actions[4], "accept");
+        assert_eq!(trace.steps[2].symbols, vec!['a', 'A']);
+        assert_eq!(trace.steps[2].input, vec!['b']);
+
+        let table_text = trace.to_string();
+        let lines: Vec<&str> = table_text.lines().collect();
+        assert_eq!(lines.len(), 7);
+        assert!(lines[0].starts_with("step | states"));
+        let cols: Vec<&str> = lines[2].split(" | ").map(|x| x.trim()).collect();
+        assert_eq!(cols, vec!["0", "0", "", "ab$", actions[0].as_str()]);
+        assert_eq!(lines[2].find('|'), lines[6].find('|'));
+
+        let csv = trace.to_csv();
+        assert!(csv.starts_with("step,states,symbols,input,action\n0,0,,ab$,shift"));
+        assert!(csv.contains(",\"reduce 1 (A -> a A b), goto "));
+        let json = trace.to_json();
+        assert!(json.starts_with(
+            "[{\"states\":[0],\"symbols\":[],\"input\":\"ab\",\"action\":{\"kind\":\"shift\""
+        ));
+        assert!(json.contains("\"text\":\"A -> eps\""));
+    }
+
+    #[test]
+    fn error() {
+        let g = Grammar::from_text(include_str!("../examples/example.gram")).unwrap();
+        let mut graph = LR1Graph::new();
+        graph.construct(LR1Node::default(&g));
+        let table = LR1Table::new(graph, &g);
+
+        let err = StackAutomata::new(&table, "a+*a").run().unwrap_err();
+        assert_eq!(err.offset, 2);
+        assert_eq!(err.found, Sym::Normal('*'));
+        assert_eq!(err.expected, vec![Sym::Normal('('), Sym::Normal('a')]);
+        assert_eq!(
+            err.to_string(),
+            "error: unexpected '*' at 2 in state ".to_string()
+                + &err.state.to_string()
+                + ", expected '(', 'a'\na+*a\n  ^"
+        );
+
+        let err = StackAutomata::new(&table, "(a").run().unwrap_err();
+        assert_eq!(err.offset, 2);
+        assert_eq!(err.found, Sym::Eps);
+        assert!(err.expected.contains(&Sym::Normal(')')));
+        assert!(err.to_string().contains("unexpected end of input"));
+    }
+
+    #[test]
+    fn epsilon_tree() {
+        let g = Grammar::from_text(include_str!("../examples/simple.gram")).unwrap();
+        let tree = parse(&g, "ab").unwrap();
+        assert_eq!(tree.sexp(), "(S (A a (A) b))");
+        assert_eq!(
+            tree.indented(),
+            "S (0) \"ab\"\n  A (1) \"ab\"\n    a @0\n    A (2) \"\"\n    b @1\n"
+        );
+    }
+
+    // table with one goto left out
+    struct NoGoto<'a>(&'a dyn Table, usize, char);
+
+    impl Display for NoGoto<'_> {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            self.0.fmt(f)
+        }
+    }
+
+    impl Table for NoGoto<'_> {
+        fn get_action(&self, state: usize, sym: Sym) -> Option<Action> {
+            self.0.get_action(state, sym)
+        }
+
+        fn get_goto(&self, state: usize, chr: char) -> Option<usize> {
+            match (state, chr) == (self.1, self.2) {
+                true => None,
+                false => self.0.get_goto(state, chr),
+            }
+        }
+
+        fn get_char(&self, state: usize) -> Option<char> {
+            self.0.get_char(state)
+        }
+
+        fn states(&self) -> usize {
+            self.0.states()
+        }
+
+        fn grammar(&self) -> &Grammar {
+            self.0.grammar()
+        }
+
+        fn name(&self) -> &str {
+            self.0.name()
+        }
+    }
 
-    pub(crate) fn get_goto(&self, c: char) -> Option<usize> {
-        let state = self.top()?;
-        self.table.get_goto(state, c)
+    #[test]
+    fn missing_goto() {
+        let g = Grammar::from_text(include_str!("../examples/simple.gram")).unwrap();
+        let mut graph = LR1Graph::new();
+        graph.construct(LR1Node::default(&g));
+        let table = LR1Table::new(graph, &g);
+        let a = table.get_goto(0, 'a').unwrap();
+        let b = table
+            .get_goto(table.get_goto(a, 'A').unwrap(), 'b')
+            .unwrap();
+        let broken = NoGoto(&table, 0, 'A');
+        let mut autom = StackAutomata::new(&broken, "ab");
+        for _ in 0..3 {
+            autom.step().unwrap();
+        }
+        // reduction of A -> a A b has nowhere to go, stack stays as it was
+        let err = autom.step().unwrap_err();
+        assert_eq!(err.state, b);
+        assert_eq!(err.found, Sym::Eps);
+        assert_eq!(autom.top(), Some(b));
     }
 }
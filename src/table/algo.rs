@@ -0,0 +1,178 @@
+use std::{fmt::Display, str::FromStr};
+
+use crate::{
+    grammar::{Grammar, Sym},
+    graph::{
+        lalrgraph::LALRGraph,
+        lalrnode::LALRNode,
+        lr0node::LR0Node,
+        lr1graph::LR1Node,
+        lrgraph::{state_items, LR0Graph, LR1Graph, LRGraph, StateItems},
+        lrnode::LRNode,
+    },
+    table::{
+        classic::ClassicTable,
+        lr0table::LR0Table,
+        lr1table::LR1Table,
+        lrtable::{lookahead, Action, Table},
+        slr1table::SLR1Table,
+    },
+};
+
+/// Construction of the table
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Algo {
+    LR0,
+    SLR1,
+    LALR1,
+    LR1,
+}
+
+impl FromStr for Algo {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "lr0" => Ok(Algo::LR0),
+            "slr1" => Ok(Algo::SLR1),
+            "lalr1" => Ok(Algo::LALR1),
+            "lr1" => Ok(Algo::LR1),
+            _ => Err(format!(
+                "unknown algorithm {}, use lr0, slr1, lalr1 or lr1",
+                s
+            )),
+        }
+    }
+}
+
+impl Display for Algo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Algo::LR0 => write!(f, "lr0"),
+            Algo::SLR1 => write!(f, "slr1"),
+            Algo::LALR1 => write!(f, "lalr1"),
+            Algo::LR1 => write!(f, "lr1"),
+        }
+    }
+}
+
+impl Algo {
+    pub fn build(&self, g: &Grammar) -> Box<dyn Table> {
+        match self {
+            Algo::LR0 => {
+                let mut graph = LR0Graph::new();
+                graph.construct(LR0Node::default(g));
+                Box::new(LR0Table::new(graph, g))
+            }
+            Algo::SLR1 => {
+                let mut graph = LR0Graph::new();
+                graph.construct(LR0Node::default(g));
+                Box::new(SLR1Table::new(graph, g))
+            }
+            Algo::LR1 => {
+                let mut graph = LR1Graph::new();
+                graph.construct(LR1Node::default(g));
+                Box::new(LR1Table::new(graph, g))
+            }
+            Algo::LALR1 => {
+                let mut graph = LALRGraph::new();
+                graph.construct(LALRNode::default(g));
+                let mut table = LR1Table::new(graph, g);
+                table.name = "LALR1Table".to_string();
+                Box::new(table)
+            }
+        }
+    }
+
+    /// Algorithm that built the table, from its [`Table::name`]
+    pub fn of(table: &dyn Table) -> Option<Algo> {
+        match table.name() {
+            "LR0Table" => Some(Algo::LR0),
+            "SLR1Table" => Some(Algo::SLR1),
+            "LALR1Table" => Some(Algo::LALR1),
+            "LR1Table" => Some(Algo::LR1),
+            _ => None,
+        }
+    }
+
+    /// Table in the classic ACTION/GOTO layout with targets of shifts
+    pub fn classic(&self, g: &Grammar) -> ClassicTable {
+        ClassicTable::new(self.build(g).as_ref())
+    }
+
+    /// States of automaton with items and transitions
+    pub fn states(&self, g: &Grammar) -> Vec<String> {
+        self.state_items(g)
+            .iter()
+            .enumerate()
+            .map(|(i, x)| x.describe(i))
+            .collect()
+    }
+
+    pub(crate) fn state_items(&self, g: &Grammar) -> Vec<StateItems> {
+        match self {
+            Algo::LR0 | Algo::SLR1 => {
+                let mut graph = LR0Graph::new();
+                graph.construct(LR0Node::default(g));
+                state_items(&graph)
+            }
+            Algo::LR1 => {
+                let mut graph = LR1Graph::new();
+                graph.construct(LR1Node::default(g));
+                state_items(&graph)
+            }
+            Algo::LALR1 => {
+                let mut graph = LALRGraph::new();
+                graph.construct(LALRNode::default(g));
+                state_items(&graph)
+            }
+        }
+    }
+}
+
+/// Cell with more actions, symbols with same actions
+/// in one state are grouped together
+#[derive(Debug, PartialEq, Eq)]
+pub struct Conflict {
+    pub state: usize,
+    pub syms: Vec<Sym>,
+    pub actions: Vec<Action>,
+}
+
+impl Display for Conflict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let syms: Vec<String> = self.syms.iter().map(|x| x.to_string()).collect();
+        let actions: Vec<String> = self.actions.iter().map(|x| x.to_string()).collect();
+        write!(
+            f,
+            "state {} on {} : {}",
+            self.state,
+            syms.join(" "),
+            actions.join("/")
+        )
+    }
+}
+
+pub fn conflicts(table: &dyn Table) -> Vec<Conflict> {
+    let syms = lookahead(table.grammar());
+
+    let mut res: Vec<Conflict> = vec![];
+    for state in 0..table.states() {
+        let start = res.len();
+        for s in &syms {
+            let actions = table.get_actions(state, *s);
+            if actions.len() < 2 {
+                continue;
+            }
+            match res[start..].iter_mut().find(|x| x.actions == actions) {
+                Some(c) => c.syms.push(*s),
+                None => res.push(Conflict {
+                    state,
+                    syms: vec![*s],
+                    actions,
+                }),
+            }
+        }
+    }
+    res
+}
@@ -0,0 +1,202 @@
+use std::{collections::HashMap, fmt::Display};
+
+use crate::{
+    grammar::{Grammar, Sym},
+    table::{
+        lrtable::{lookahead, sorted, Action, ClassicAction, Table},
+        render::{Renderer, Text},
+    },
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct State {
+    sym: char,
+    // every action of the cell, more of them for conflict
+    action: HashMap<Sym, Vec<ClassicAction>>,
+    // non-terminals only
+    goto: HashMap<char, usize>,
+}
+
+/// Table in the classic layout: ACTION over terminals and end of input
+/// with targets of shifts (`s5`, `r3`, `acc`) and GOTO over non-terminals
+/// only. It can be made from any table and drives the automaton as well.
+/// Shifts without transition (LR(0) rows) are left out, same as in
+/// textbook tables.
+#[derive(Debug, Clone)]
+pub struct ClassicTable {
+    name: String,
+    gramm: Grammar,
+    states: Vec<State>,
+}
+
+impl ClassicTable {
+    pub fn new(table: &dyn Table) -> Self {
+        let gramm = table.grammar().clone();
+        let lookahead = lookahead(&gramm);
+        let non_terms = sorted(gramm.non_terms());
+        let states = (0..table.states())
+            .map(|s| State {
+                sym: table.get_char(s).unwrap(),
+                action: lookahead
+                    .iter()
+                    .map(|x| (*x, classic_actions(table, s, *x)))
+                    .filter(|(_, a)| !a.is_empty())
+                    .collect(),
+                goto: non_terms
+                    .iter()
+                    .filter_map(|c| Some((*c, table.get_goto(s, *c)?)))
+                    .collect(),
+            })
+            .collect();
+        Self {
+            name: table.name().to_string(),
+            gramm,
+            states,
+        }
+    }
+
+    /// Every action of the cell, more of them for conflict
+    pub fn action(&self, state: usize, sym: Sym) -> &[ClassicAction] {
+        self.states
+            .get(state)
+            .and_then(|x| x.action.get(&sym))
+            .map_or(&[], |x| x.as_slice())
+    }
+
+    /// Goto after reduction to non-terminal
+    pub fn goto(&self, state: usize, non_term: char) -> Option<usize> {
+        self.states.get(state)?.goto.get(&non_term).copied()
+    }
+}
+
+// actions of the cell with targets of shifts, shift without target is dropped
+pub(crate) fn classic_actions(table: &dyn Table, state: usize, sym: Sym) -> Vec<ClassicAction> {
+    table
+        .get_actions(state, sym)
+        .into_iter()
+        .filter_map(|a| match (a, sym) {
+            (Action::Shift, Sym::Normal(c)) => {
+                Some(ClassicAction::Shift(table.get_goto(state, c)?))
+            }
+            (Action::Reduction(r), _) => Some(ClassicAction::Reduce(r)),
+            (Action::Accept, _) => Some(ClassicAction::Accept),
+            _ => None,
+        })
+        .collect()
+}
+
+impl Display for ClassicTable {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&Text.render_classic(self))
+    }
+}
+
+impl Table for ClassicTable {
+    fn get_action(&self, state: usize, sym: Sym) -> Option<Action> {
+        match self.action(state, sym) {
+            [] => None,
+            [ClassicAction::Shift(_)] => Some(Action::Shift),
+            [ClassicAction::Reduce(r)] => Some(Action::Reduction(*r)),
+            [ClassicAction::Accept] => Some(Action::Accept),
+            _ => Some(Action::Error),
+        }
+    }
+
+    // target of shift for terminal
+    fn get_goto(&self, state: usize, chr: char) -> Option<usize> {
+        if self.gramm.is_non_term(&chr) {
+            return self.goto(state, chr);
+        }
+        self.action(state, Sym::Normal(chr))
+            .iter()
+            .find_map(|a| match a {
+                ClassicAction::Shift(x) => Some(*x),
+                _ => None,
+            })
+    }
+
+    fn get_char(&self, state: usize) -> Option<char> {
+        Some(self.states.get(state)?.sym)
+    }
+
+    fn states(&self) -> usize {
+        self.states.len()
+    }
+
+    fn grammar(&self) -> &Grammar {
+        &self.gramm
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn get_actions(&self, state: usize, sym: Sym) -> Vec<Action> {
+        self.action(state, sym)
+            .iter()
+            .map(|a| match a {
+                ClassicAction::Shift(_) => Action::Shift,
+                ClassicAction::Reduce(r) => Action::Reduction(*r),
+                ClassicAction::Accept => Action::Accept,
+            })
+            .collect()
+    }
+
+    fn get_classic(&self, state: usize, sym: Sym) -> Option<ClassicAction> {
+        match self.action(state, sym) {
+            [x] => Some(*x),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        table::algo::{conflicts, Algo},
+        testing::{assert_same_parses, tables},
+    };
+
+    use super::*;
+
+    #[test]
+    fn same_parses() {
+        for (_, algo, table) in tables() {
+            let classic = ClassicTable::new(table.as_ref());
+            // LR(0) marks shift on every terminal of shifting state,
+            // classic cell has shift only where there is transition
+            // so conflicting rows differ (the same states conflict)
+            let states = |t: &dyn Table| {
+                let mut res: Vec<usize> = conflicts(t).iter().map(|x| x.state).collect();
+                res.dedup();
+                res
+            };
+            assert_eq!(states(&classic), states(table.as_ref()));
+            if algo == Algo::LR0 && !conflicts(table.as_ref()).is_empty() {
+                continue;
+            }
+            assert_eq!(conflicts(&classic), conflicts(table.as_ref()));
+            assert_same_parses(table.as_ref(), &classic);
+        }
+    }
+
+    #[test]
+    fn layout() {
+        let g = Grammar::from_text(include_str!("../../examples/simple.gram")).unwrap();
+        let table = Algo::LR1.classic(&g);
+        let text = table.to_string();
+        let mut lines = text.lines();
+        assert_eq!(lines.next(), Some("LR1Table"));
+        assert_eq!(lines.next(), Some("state\t|a\tb\teps\t|A\tS\t"));
+        // shift on a carries target, goto only over non-terminals
+        let a = table.get_goto(0, 'a').unwrap();
+        assert_eq!(table.action(0, Sym::Normal('a')), [ClassicAction::Shift(a)]);
+        assert_eq!(
+            table.get_classic(0, Sym::Normal('a')),
+            Some(ClassicAction::Shift(a))
+        );
+        assert_eq!(table.goto(0, 'a'), None);
+        assert!(table.goto(0, 'A').is_some());
+        assert!(text.contains(&format!("0\t|s{}\t", a)));
+    }
+}
@@ -0,0 +1,384 @@
+use std::{collections::HashMap, fmt::Display, mem::size_of};
+
+use crate::{
+    grammar::{Grammar, Sym},
+    table::{
+        lrtable::{cell_actions, lookahead, sorted, Action, Table},
+        render::{Renderer, Text},
+    },
+};
+
+// action codes in packed rows
+const CONFLICT: u32 = 0;
+const SHIFT: u32 = 1;
+const ACCEPT: u32 = 2;
+// reduction by r is REDUCE + r
+const REDUCE: u32 = 3;
+const NONE: u32 = u32::MAX;
+
+/// Rows packed by row displacement (comb): every distinct row gets
+/// base in one shared array and cell `col` of the row is at `base + col`
+/// when `check` of that place names the row. Identical rows are stored
+/// once, so all empty rows share one error row.
+#[derive(Debug, Clone)]
+struct Packed {
+    // state -> distinct row
+    rows: Vec<u32>,
+    base: Vec<u32>,
+    values: Vec<u32>,
+    check: Vec<u32>,
+}
+
+impl Packed {
+    fn new(rows: Vec<Vec<(usize, u32)>>) -> Self {
+        let mut distinct: Vec<Vec<(usize, u32)>> = vec![];
+        let mut index: HashMap<Vec<(usize, u32)>, u32> = HashMap::new();
+        let rows = rows
+            .into_iter()
+            .map(|r| {
+                *index.entry(r.clone()).or_insert_with(|| {
+                    distinct.push(r);
+                    distinct.len() as u32 - 1
+                })
+            })
+            .collect();
+
+        // first fit, longest rows go first while the array is sparse
+        let mut order: Vec<usize> = (0..distinct.len()).collect();
+        order.sort_by_key(|x| std::cmp::Reverse(distinct[*x].len()));
+        let mut base = vec![0; distinct.len()];
+        let mut values = vec![];
+        let mut check: Vec<u32> = vec![];
+        for r in order {
+            let row = &distinct[r];
+            let free = |b: usize| {
+                row.iter()
+                    .all(|(c, _)| check.get(b + c).is_none_or(|x| *x == NONE))
+            };
+            let b = (0..).find(|b| free(*b)).unwrap();
+            for (c, v) in row {
+                if check.len() <= b + c {
+                    check.resize(b + c + 1, NONE);
+                    values.resize(b + c + 1, NONE);
+                }
+                check[b + c] = r as u32;
+                values[b + c] = *v;
+            }
+            base[r] = b as u32;
+        }
+        Self {
+            rows,
+            base,
+            values,
+            check,
+        }
+    }
+
+    fn get(&self, state: usize, col: usize) -> Option<u32> {
+        let row = *self.rows.get(state)?;
+        let i = self.base[row as usize] as usize + col;
+        match self.check.get(i) {
+            Some(x) if *x == row => Some(self.values[i]),
+            _ => None,
+        }
+    }
+
+    fn distinct(&self) -> usize {
+        self.base.len()
+    }
+
+    fn bytes(&self) -> usize {
+        size_of::<u32>()
+            * (self.rows.len() + self.base.len() + self.values.len() + self.check.len())
+    }
+}
+
+/// Table copied out of any other table into compressed arrays. Every
+/// state can have default reduction (the most common one in its row),
+/// it is used for every lookahead without explicit action, so rows of
+/// states with one reduction become empty. Errors in such states are
+/// found later, after the reduction and before the next shift, in the
+/// same place of the input. Action and goto rows are packed by row
+/// displacement.
+#[derive(Debug, Clone)]
+pub struct CompressedTable {
+    name: String,
+    gramm: Grammar,
+    chars: Vec<char>,
+    defaults: Vec<Option<usize>>,
+    // terminals, end of input after them
+    lookahead: HashMap<Sym, usize>,
+    // terminals and non-terminals, goto on terminal is target of shift
+    syms: HashMap<char, usize>,
+    action: Packed,
+    goto: Packed,
+    // actions of conflicting cells
+    conflicts: HashMap<(usize, Sym), Vec<Action>>,
+    original: usize,
+}
+
+/// Size of table before and after compression
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Compression {
+    pub original: usize,
+    pub compressed: usize,
+    pub states: usize,
+    pub action_rows: usize,
+    pub goto_rows: usize,
+}
+
+impl Compression {
+    pub fn ratio(&self) -> f64 {
+        self.original as f64 / self.compressed.max(1) as f64
+    }
+}
+
+impl Display for Compression {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} B -> {} B ({:.1}x), {} states, {} action rows, {} goto rows",
+            self.original,
+            self.compressed,
+            self.ratio(),
+            self.states,
+            self.action_rows,
+            self.goto_rows
+        )
+    }
+}
+
+impl CompressedTable {
+    pub fn new(table: &dyn Table) -> Self {
+        let gramm = table.grammar().clone();
+        let lookahead = lookahead(&gramm);
+        let syms = sorted(&gramm.terms().union(gramm.non_terms()).copied().collect());
+
+        let mut defaults = vec![];
+        let mut actions = vec![];
+        let mut gotos = vec![];
+        let mut conflicts = HashMap::new();
+        for s in 0..table.states() {
+            let cells: Vec<Vec<Action>> =
+                lookahead.iter().map(|x| table.get_actions(s, *x)).collect();
+            let default = most_common(&cells);
+            let mut row = vec![];
+            for (col, all) in cells.into_iter().enumerate() {
+                let code = match all.as_slice() {
+                    [] => continue,
+                    [Action::Reduction(r)] if Some(*r) == default => continue,
+                    [Action::Shift] => SHIFT,
+                    [Action::Accept] => ACCEPT,
+                    [Action::Reduction(r)] => REDUCE + *r as u32,
+                    _ => {
+                        conflicts.insert((s, lookahead[col]), all);
+                        CONFLICT
+                    }
+                };
+                row.push((col, code));
+            }
+            defaults.push(default);
+            actions.push(row);
+            gotos.push(
+                syms.iter()
+                    .enumerate()
+                    .filter_map(|(i, c)| Some((i, table.get_goto(s, *c)? as u32)))
+                    .collect(),
+            );
+        }
+
+        Self {
+            name: table.name().to_string(),
+            chars: (0..table.states())
+                .map(|s| table.get_char(s).unwrap())
+                .collect(),
+            original: original_size(table, &syms),
+            gramm,
+            defaults,
+            lookahead: lookahead
+                .into_iter()
+                .enumerate()
+                .map(|(i, x)| (x, i))
+                .collect(),
+            syms: syms.into_iter().enumerate().map(|(i, x)| (x, i)).collect(),
+            action: Packed::new(actions),
+            goto: Packed::new(gotos),
+            conflicts,
+        }
+    }
+
+    /// Reduction used for lookaheads without explicit action
+    pub fn default_reduction(&self, state: usize) -> Option<usize> {
+        self.defaults.get(state).copied().flatten()
+    }
+
+    /// Size of the compressed arrays against the table it was made from
+    pub fn stats(&self) -> Compression {
+        let conflicts: usize = self
+            .conflicts
+            .values()
+            .map(|x| size_of::<(usize, Sym)>() + x.len() * size_of::<Action>())
+            .sum();
+        let compressed = self.action.bytes()
+            + self.goto.bytes()
+            + self.chars.len() * size_of::<char>()
+            + self.defaults.len() * size_of::<u32>()
+            + conflicts;
+        Compression {
+            original: self.original,
+            compressed,
+            states: self.chars.len(),
+            action_rows: self.action.distinct(),
+            goto_rows: self.goto.distinct(),
+        }
+    }
+}
+
+// reduction in most cells, the smaller rule wins ties
+fn most_common(cells: &[Vec<Action>]) -> Option<usize> {
+    let mut count: HashMap<usize, usize> = HashMap::new();
+    for all in cells {
+        if let [Action::Reduction(r)] = all.as_slice() {
+            *count.entry(*r).or_default() += 1;
+        }
+    }
+    count
+        .into_iter()
+        .max_by_key(|(r, n)| (*n, std::cmp::Reverse(*r)))
+        .map(|(r, _)| r)
+}
+
+// estimate of the current layout: one map entry for every action
+// (non-terminals included) and every goto, conflicts kept aside
+fn original_size(table: &dyn Table, syms: &[char]) -> usize {
+    let mut res = 0;
+    for s in 0..table.states() {
+        for x in syms.iter().map(|c| Sym::Normal(*c)).chain([Sym::Eps]) {
+            if table.get_action(s, x).is_some() {
+                res += size_of::<(Sym, Action)>();
+            }
+            if let Sym::Normal(c) = x {
+                if table.get_goto(s, c).is_some() {
+                    res += size_of::<(char, usize)>();
+                }
+            }
+            let all = table.get_actions(s, x);
+            if all.len() > 1 {
+                res += size_of::<Sym>() + all.len() * size_of::<Action>();
+            }
+        }
+        res += size_of::<char>();
+    }
+    res
+}
+
+impl Display for CompressedTable {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&Text.render(self))
+    }
+}
+
+impl Table for CompressedTable {
+    fn get_action(&self, state: usize, sym: Sym) -> Option<Action> {
+        let col = *self.lookahead.get(&sym)?;
+        match self.action.get(state, col) {
+            Some(CONFLICT) => Some(Action::Error),
+            Some(SHIFT) => Some(Action::Shift),
+            Some(ACCEPT) => Some(Action::Accept),
+            Some(x) => Some(Action::Reduction((x - REDUCE) as usize)),
+            None => self.default_reduction(state).map(Action::Reduction),
+        }
+    }
+
+    fn get_goto(&self, state: usize, chr: char) -> Option<usize> {
+        let col = *self.syms.get(&chr)?;
+        self.goto.get(state, col).map(|x| x as usize)
+    }
+
+    fn get_char(&self, state: usize) -> Option<char> {
+        self.chars.get(state).copied()
+    }
+
+    fn states(&self) -> usize {
+        self.chars.len()
+    }
+
+    fn grammar(&self) -> &Grammar {
+        &self.gramm
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn get_actions(&self, state: usize, sym: Sym) -> Vec<Action> {
+        match self.conflicts.get(&(state, sym)) {
+            Some(all) => all.clone(),
+            None => cell_actions(self.get_action(state, sym)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        table::algo::{conflicts, Algo},
+        testing::{assert_same_parses, tables, ALGOS},
+    };
+
+    use super::*;
+
+    #[test]
+    fn same_parses() {
+        for (name, _, table) in tables() {
+            let compressed = CompressedTable::new(table.as_ref());
+            assert_eq!(
+                conflicts(&compressed).len(),
+                conflicts(table.as_ref()).len(),
+                "{}",
+                name
+            );
+            assert_same_parses(table.as_ref(), &compressed);
+        }
+    }
+
+    #[test]
+    fn exact_cells() {
+        let g = Grammar::from_text(include_str!("../../examples/assign.gram")).unwrap();
+        for algo in ALGOS {
+            let table = algo.build(&g);
+            let compressed = CompressedTable::new(table.as_ref());
+            let mut syms: Vec<char> = g.terms().union(g.non_terms()).copied().collect();
+            syms.sort();
+            for s in 0..table.states() {
+                assert_eq!(compressed.get_char(s), table.get_char(s));
+                for c in &syms {
+                    assert_eq!(compressed.get_goto(s, *c), table.get_goto(s, *c));
+                }
+                for x in lookahead(&g) {
+                    let all = table.get_actions(s, x);
+                    // default reduction fills only cells without action
+                    match all.is_empty() {
+                        true => assert_eq!(
+                            compressed.get_actions(s, x),
+                            cell_actions(compressed.default_reduction(s).map(Action::Reduction))
+                        ),
+                        false => assert_eq!(compressed.get_actions(s, x), all),
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn ratio() {
+        let g = Grammar::from_text(include_str!("../../examples/better.gram")).unwrap();
+        let table = Algo::LR1.build(&g);
+        let stats = CompressedTable::new(table.as_ref()).stats();
+        assert!(stats.ratio() > 2.0, "{}", stats);
+        assert!(stats.action_rows < stats.states);
+        assert!(stats
+            .to_string()
+            .starts_with(&format!("{} B -> ", stats.original)));
+    }
+}
@@ -0,0 +1,195 @@
+use std::{collections::HashMap, fmt::Display};
+
+use crate::{
+    grammar::{Grammar, Sym},
+    table::{
+        lrtable::{cell_actions, lookahead, sorted, Action, Table},
+        render::{Renderer, Text},
+    },
+};
+
+const NONE: u32 = u32::MAX;
+
+/// Table copied out of any other table into flat arrays. Terminals,
+/// end of input and non-terminals get contiguous indices (ASCII symbols
+/// through a lookup array), a cell is then plain indexing instead of
+/// hashing, which is what `StackAutomata` does on every step.
+#[derive(Debug, Clone)]
+pub struct DenseTable {
+    name: String,
+    gramm: Grammar,
+    chars: Vec<char>,
+    // index of ASCII symbols, NONE for others
+    ascii: [u32; 128],
+    // sorted non ASCII symbols with their index
+    others: Vec<(char, u32)>,
+    // index of end of input, terminals are before it
+    eps: usize,
+    // columns of one row
+    width: usize,
+    // states * width, end of input and terminals only
+    action: Vec<Option<Action>>,
+    goto: Vec<u32>,
+    // actions of conflicting cells
+    conflicts: HashMap<(usize, Sym), Vec<Action>>,
+}
+
+impl DenseTable {
+    pub fn new(table: &dyn Table) -> Self {
+        let gramm = table.grammar().clone();
+        let terms = sorted(gramm.terms());
+        let eps = terms.len();
+        let syms: Vec<char> = terms
+            .iter()
+            .copied()
+            .chain(sorted(gramm.non_terms()))
+            .collect();
+        let width = syms.len() + 1;
+        let column = |i: usize| if i < eps { i } else { i + 1 };
+
+        let mut ascii = [NONE; 128];
+        let mut others = vec![];
+        for (i, c) in syms.iter().enumerate() {
+            match (*c as usize) < ascii.len() {
+                true => ascii[*c as usize] = column(i) as u32,
+                false => others.push((*c, column(i) as u32)),
+            }
+        }
+        others.sort();
+
+        let states = table.states();
+        let mut action = vec![None; states * width];
+        let mut goto = vec![NONE; states * width];
+        let mut conflicts = HashMap::new();
+        for s in 0..states {
+            for (i, x) in lookahead(&gramm).into_iter().enumerate() {
+                action[s * width + i] = table.get_action(s, x);
+                let all = table.get_actions(s, x);
+                if all.len() > 1 {
+                    conflicts.insert((s, x), all);
+                }
+            }
+            for (i, c) in syms.iter().enumerate() {
+                if let Some(t) = table.get_goto(s, *c) {
+                    goto[s * width + column(i)] = t as u32;
+                }
+            }
+        }
+
+        Self {
+            name: table.name().to_string(),
+            chars: (0..states).map(|s| table.get_char(s).unwrap()).collect(),
+            gramm,
+            ascii,
+            others,
+            eps,
+            width,
+            action,
+            goto,
+            conflicts,
+        }
+    }
+
+    fn index(&self, c: char) -> Option<usize> {
+        let res = match self.ascii.get(c as usize) {
+            Some(x) => *x,
+            None => {
+                let i = self.others.binary_search_by_key(&c, |x| x.0).ok()?;
+                self.others[i].1
+            }
+        };
+        (res != NONE).then_some(res as usize)
+    }
+
+    fn cell(&self, state: usize, col: usize) -> Option<usize> {
+        (state < self.chars.len()).then_some(state * self.width + col)
+    }
+}
+
+impl Display for DenseTable {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&Text.render(self))
+    }
+}
+
+impl Table for DenseTable {
+    fn get_action(&self, state: usize, sym: Sym) -> Option<Action> {
+        let col = match sym {
+            Sym::Normal(c) => self.index(c).filter(|x| *x < self.eps)?,
+            Sym::Eps => self.eps,
+        };
+        self.action[self.cell(state, col)?]
+    }
+
+    fn get_goto(&self, state: usize, chr: char) -> Option<usize> {
+        let res = self.goto[self.cell(state, self.index(chr)?)?];
+        (res != NONE).then_some(res as usize)
+    }
+
+    fn get_char(&self, state: usize) -> Option<char> {
+        self.chars.get(state).copied()
+    }
+
+    fn states(&self) -> usize {
+        self.chars.len()
+    }
+
+    fn grammar(&self) -> &Grammar {
+        &self.gramm
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn get_actions(&self, state: usize, sym: Sym) -> Vec<Action> {
+        match self.conflicts.get(&(state, sym)) {
+            Some(all) => all.clone(),
+            None => cell_actions(self.get_action(state, sym)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        stackautomata::StackAutomata,
+        table::algo::Algo,
+        testing::{assert_same_parses, tables},
+    };
+
+    use super::*;
+
+    #[test]
+    fn same_cells() {
+        for (_, _, table) in tables() {
+            let g = table.grammar();
+            let syms: Vec<char> = g.terms().union(g.non_terms()).copied().collect();
+            let dense = DenseTable::new(table.as_ref());
+            assert_eq!(dense.states(), table.states());
+            assert_eq!(dense.name(), table.name());
+            for s in 0..table.states() {
+                assert_eq!(dense.get_char(s), table.get_char(s));
+                for c in &syms {
+                    assert_eq!(dense.get_goto(s, *c), table.get_goto(s, *c));
+                }
+                for x in lookahead(g) {
+                    assert_eq!(dense.get_action(s, x), table.get_action(s, x));
+                    assert_eq!(dense.get_actions(s, x), table.get_actions(s, x));
+                }
+            }
+            assert_eq!(dense.get_goto(table.states(), syms[0]), None);
+            assert_eq!(dense.get_goto(0, '\u{3bb}'), None);
+            assert_same_parses(table.as_ref(), &dense);
+        }
+    }
+
+    #[test]
+    fn non_ascii() {
+        let g = Grammar::from_text("S A\nλ →\nS->A\nA->λA\nA->→\n").unwrap();
+        let table = DenseTable::new(Algo::LALR1.build(&g).as_ref());
+        let tree = StackAutomata::new(&table, "λλ→").run().unwrap();
+        assert_eq!(tree.text(), "λλ→");
+        assert!(StackAutomata::new(&table, "λa").run().is_err());
+    }
+}
@@ -1,60 +1,60 @@
 use std::{collections::HashMap, fmt::Display};
 
-use crate::{grammar::{Grammar, Sym}, graph::{lrgraph::{LR0Graph, LRGraph}, rule::LRRule, lrnode::LRNode}, table::lrtable::{Action, Table}};
+use crate::{
+    grammar::{Grammar, Sym},
+    graph::{
+        lrgraph::{LR0Graph, LRGraph},
+        lrnode::LRNode,
+        rule::LRRule,
+    },
+    table::{
+        lrtable::{cell_actions, Action, Table},
+        render::{Renderer, Text},
+    },
+};
 
 #[derive(Debug)]
 pub(crate) struct LR0Table {
     action: Vec<(char, Action)>,
     goto: Vec<HashMap<char, usize>>,
-    syms : Vec<char>,
+    conflicts: Vec<Vec<Action>>,
+    gramm: Grammar,
 }
 
 impl Display for LR0Table {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        writeln!(f, "LR0Table")?;
-        write!(f, "state\taction\t")?;
-        for c in &self.syms {
-            write!(f, "{}\t", c)?;
-        }
-        writeln!(f, "")?;
-        for i in 0..self.action.len() {
-            let (c, tmp) = &self.action[i];
-            write!(f, "{}{}\t{}\t", c, i, tmp)?;
-            for s in &self.syms {
-                match self.goto[i].get(s) {
-                    Some(g) => write!(f, "{}\t", g),
-                    None => write!(f, " \t"),
-                }?;
-            }
-            writeln!(f, "")?;
-        }
-        Ok(())
+        f.write_str(&Text.render(self))
     }
 }
 
 impl LR0Table {
     pub(crate) fn new(graph: LR0Graph, g: &Grammar) -> Self {
-        let action: Vec<(char, Action)> = graph
+        let (action, conflicts): (Vec<(char, Action)>, Vec<Vec<Action>>) = graph
             .nodes()
             .iter()
             .map(|x| {
                 let mut res = Action::Empty;
+                let mut all = vec![];
                 for r in x.all_rules() {
-                    match r.get_sym(g) {
-                        Some(_) => res = res.update(Action::Shift),
-                        None => res = res.update(Action::Reduction(r.rule)),
-                    }
+                    let a = match r.get_sym(g) {
+                        Some(_) => Action::Shift,
+                        None => Action::Reduction(r.rule),
+                    };
+                    res = res.update(a);
+                    a.push_into(&mut all);
+                }
+                if all.len() < 2 {
+                    all.clear();
                 }
-                (x.from(), res)
+                ((x.from(), res), all)
             })
-            .collect();
-        let mut syms : Vec<char> = vec![];
-        g.terms.union(&g.non_terms).for_each(|x| {syms.push(*x)});
+            .unzip();
 
         Self {
             action,
             goto: graph.edges().to_owned(),
-            syms,
+            conflicts,
+            gramm: g.clone(),
         }
     }
 }
@@ -73,4 +73,23 @@ impl Table for LR0Table {
         let goto_line = self.goto.get(state)?;
         goto_line.get(&c).copied()
     }
+
+    fn states(&self) -> usize {
+        self.action.len()
+    }
+
+    fn grammar(&self) -> &Grammar {
+        &self.gramm
+    }
+
+    fn name(&self) -> &str {
+        "LR0Table"
+    }
+
+    fn get_actions(&self, state: usize, _: Sym) -> Vec<Action> {
+        match self.conflicts.get(state) {
+            Some(all) if !all.is_empty() => all.clone(),
+            _ => cell_actions(self.get_action(state, Sym::Eps)),
+        }
+    }
 }
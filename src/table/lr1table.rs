@@ -1,4 +1,4 @@
-use std::{collections::HashMap, fmt::Display, marker::PhantomData};
+use std::{collections::HashMap, fmt::Display};
 
 use crate::{
     grammar::{Grammar, Sym},
@@ -10,83 +10,45 @@ use crate::{
     },
 };
 
-use super::lrtable::{Action, Table};
+use super::{
+    lrtable::{cell_actions, Action, Table},
+    render::{Renderer, Text},
+};
 
-pub(crate) struct LR1Table<'a, N, T>
-where
-    N: LRNode<'a, LR1Rule>,
-    T: LRFollowGraph<'a, N>,
-{
+pub(crate) struct LR1Table {
     action: Vec<(char, HashMap<Sym, Action>)>,
     goto: Vec<HashMap<char, usize>>,
-    syms: Vec<char>,
-    gramm: &'a Grammar,
-    phantom_n: PhantomData<&'a N>,
-    phantom_t: PhantomData<&'a T>,
+    conflicts: Vec<HashMap<Sym, Vec<Action>>>,
+    gramm: Grammar,
 
     pub name : String,
 }
 
-impl<'a, N, T>Display for LR1Table<'a, N,T> 
-where
-    N: LRNode<'a, LR1Rule>,
-    T: LRFollowGraph<'a, N>,
-{
+impl Display for LR1Table {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        writeln!(f, "{}", self.name)?;
-        write!(f, "state\t|")?;
-        for c in self.syms.iter().filter(|x| self.gramm.is_term(x)) {
-            write!(f, "{}\t", c)?;
-        }
-        write!(f, "{}\t|", Sym::Eps)?;
-        for c in &self.syms {
-            write!(f, "{}\t", c)?;
-        }
-        writeln!(f, "")?;
-        for i in 0..self.action.len() {
-            let (c, _) = &self.action[i];
-            write!(f, "{}{}\t|", c, i)?;
-            let (_, a) = &self.action[i];
-            for c in self.syms.iter().filter(|x| self.gramm.is_term(x)) {
-                match a.get(&Sym::Normal(*c)) {
-                    Some(a) => write!(f, "{}\t", a),
-                    None => write!(f, " \t"),
-                }?;
-            }
-            match a.get(&Sym::Eps) {
-                Some(a) => write!(f, "{}\t|", a),
-                None => write!(f, " \t|"),
-            }?;
-            for s in &self.syms {
-                match self.goto[i].get(s) {
-                    Some(g) => write!(f, "{}\t", g),
-                    None => write!(f, " \t"),
-                }?;
-            }
-            writeln!(f, "")?;
-        }
-        Ok(())
+        f.write_str(&Text.render(self))
     }
 }
 
-impl<'a, N, T> LR1Table<'a, N, T>
-where
-    N: LRNode<'a, LR1Rule>,
-    T: LRFollowGraph<'a, N>,
-{
-    pub(crate) fn new(graph: T, gramm: &'a Grammar) -> Self {
+impl LR1Table {
+    pub(crate) fn new<'a, N, T>(graph: T, gramm: &'a Grammar) -> Self
+    where
+        N: LRNode<'a, LR1Rule>,
+        T: LRFollowGraph<'a, N>,
+    {
         let mut syms: Vec<char> = vec![];
         gramm
             .terms
             .union(&gramm.non_terms)
             .for_each(|x| syms.push(*x));
-        let action: Vec<(char, HashMap<Sym, Action>)> = graph
+        let (action, conflicts): (Vec<_>, Vec<_>) = graph
             .nodes()
             .iter()
             .map(|x| {
                 let mut res =
                     HashMap::from_iter(syms.iter().map(|x| (Sym::Normal(*x), Action::Empty)));
                 res.insert(Sym::Eps, Action::Empty);
+                let mut all: HashMap<Sym, Vec<Action>> = HashMap::new();
                 for r in x.all_rules() {
                     match r.get_sym(gramm) {
                         Some(s) => {
@@ -95,37 +57,34 @@ where
                             }
                             let tmp = res.get(&Sym::Normal(s)).unwrap().update(Action::Shift);
                             res.insert(Sym::Normal(s), tmp);
+                            Action::Shift.push_into(all.entry(Sym::Normal(s)).or_default());
                         }
                         None => {
                             let tmp = res
                                 .get(&r.follow)
                                 .unwrap()
                                 .update(Action::Reduction(r.rule()));
-                            res.insert(r.follow.clone(), tmp);
+                            res.insert(r.follow, tmp);
+                            Action::Reduction(r.rule()).push_into(all.entry(r.follow).or_default());
                         }
                     }
                 }
-                (x.from(), res)
+                all.retain(|_, v| v.len() > 1);
+                ((x.from(), res), all)
             })
-            .collect();
+            .unzip();
 
         Self {
             action,
             goto: graph.edges().to_owned(),
-            syms,
-            gramm,
-            phantom_n : PhantomData,
-            phantom_t : PhantomData,
+            conflicts,
+            gramm: gramm.clone(),
             name : "LR1Table".to_string(),
         }
     }
 }
 
-impl<'a, N, T>Table for LR1Table<'a, N, T> 
-where
-    N: LRNode<'a, LR1Rule>,
-    T: LRFollowGraph<'a, N>,
-{
+impl Table for LR1Table {
     fn get_action(&self, state: usize, sym: Sym) -> Option<Action> {
         let (_, a) = self.action.get(state)?;
         a.get(&sym).copied()
@@ -139,4 +98,23 @@ where
         let (c, _) = self.action.get(state)?;
         Some(*c)
     }
+
+    fn states(&self) -> usize {
+        self.action.len()
+    }
+
+    fn grammar(&self) -> &Grammar {
+        &self.gramm
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn get_actions(&self, state: usize, sym: Sym) -> Vec<Action> {
+        match self.conflicts.get(state).and_then(|x| x.get(&sym)) {
+            Some(all) => all.clone(),
+            None => cell_actions(self.get_action(state, sym)),
+        }
+    }
 }
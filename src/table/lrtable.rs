@@ -1,6 +1,6 @@
-use std::fmt::Display;
+use std::{collections::HashSet, fmt::Display};
 
-use crate::grammar::Sym;
+use crate::grammar::{Grammar, Sym};
 
 #[derive(PartialEq, Eq, Debug, Clone, Copy)]
 pub enum Action {
@@ -40,10 +40,92 @@ impl Action {
             _ => Action::Error,
         }
     }
+
+    // keeps every distinct action of one cell so conflicts
+    // can be followed later (reduction by 0 is stored as accept
+    // same as in update), shift goes first and reductions by rule
+    pub(crate) fn push_into(self, all: &mut Vec<Action>) {
+        let new = match self {
+            Action::Reduction(0) => Action::Accept,
+            x => x,
+        };
+        if !all.contains(&new) {
+            all.push(new);
+            all.sort_by_key(|x| match x {
+                Action::Shift => (0, 0),
+                Action::Accept => (1, 0),
+                Action::Reduction(r) => (2, *r),
+                Action::Error | Action::Empty => (3, 0),
+            });
+        }
+    }
+}
+
+/// Action of the classic table where shift carries its target
+/// (`s5`), gotos are then only over non-terminals
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum ClassicAction {
+    Shift(usize),
+    Reduce(usize),
+    Accept,
+}
+
+impl Display for ClassicAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ClassicAction::Shift(x) => write!(f, "s{}", x),
+            ClassicAction::Reduce(x) => write!(f, "r{}", x),
+            ClassicAction::Accept => write!(f, "acc"),
+        }
+    }
 }
 
-pub trait Table: Display {
+/// Parsing table, it owns copy of its grammar so it can be kept
+/// and shared between threads independently on the original grammar
+pub trait Table: Display + Send + Sync {
     fn get_action(&self, state: usize, sym: Sym) -> Option<Action>;
     fn get_goto(&self, state: usize, chr: char) -> Option<usize>;
     fn get_char(&self, state: usize) -> Option<char>;
+    fn states(&self) -> usize;
+    fn grammar(&self) -> &Grammar;
+    fn name(&self) -> &str;
+
+    // all actions of the cell, for conflicting cell (Action::Error)
+    // these are the actions that were in conflict
+    fn get_actions(&self, state: usize, sym: Sym) -> Vec<Action> {
+        cell_actions(self.get_action(state, sym))
+    }
+
+    // action with target of shift, None for error and conflict
+    fn get_classic(&self, state: usize, sym: Sym) -> Option<ClassicAction> {
+        match (self.get_action(state, sym)?, sym) {
+            (Action::Shift, Sym::Normal(c)) => Some(ClassicAction::Shift(self.get_goto(state, c)?)),
+            (Action::Reduction(r), _) => Some(ClassicAction::Reduce(r)),
+            (Action::Accept, _) => Some(ClassicAction::Accept),
+            _ => None,
+        }
+    }
+}
+
+// actions of cell without conflict
+pub(crate) fn cell_actions(action: Option<Action>) -> Vec<Action> {
+    match action {
+        Some(Action::Error | Action::Empty) | None => vec![],
+        Some(a) => vec![a],
+    }
+}
+
+pub(crate) fn sorted(set: &HashSet<char>) -> Vec<char> {
+    let mut res: Vec<char> = set.iter().copied().collect();
+    res.sort();
+    res
+}
+
+// terminals in column order, end of input last
+pub(crate) fn lookahead(g: &Grammar) -> Vec<Sym> {
+    sorted(g.terms())
+        .into_iter()
+        .map(Sym::Normal)
+        .chain([Sym::Eps])
+        .collect()
 }
@@ -1,4 +1,10 @@
+pub mod algo;
+pub mod classic;
+pub mod compressed;
+pub mod dense;
 pub mod lr0table;
 pub mod lr1table;
 pub mod lrtable;
+pub mod render;
 pub mod slr1table;
+pub mod stored;
@@ -0,0 +1,460 @@
+use crate::{
+    grammar::Sym,
+    table::{
+        classic::classic_actions,
+        lrtable::{lookahead, sorted, Table},
+    },
+};
+
+/// Output format of parsing table, every renderer works on any `Table`
+/// with actions over terminals and end of input and gotos over symbols
+pub trait Renderer {
+    fn render(&self, table: &dyn Table) -> String;
+    /// Classic ACTION/GOTO layout of the table: shifts with targets
+    /// (`s5`), reductions (`r3`) and `acc`, gotos over non-terminals only
+    fn render_classic(&self, table: &dyn Table) -> String;
+}
+
+/// Tab separated text, used by `Display` of the tables
+pub struct Text;
+/// GitHub flavoured Markdown table
+pub struct Markdown;
+/// Comma separated values with header row
+pub struct Csv;
+/// HTML `<table>` with conflicting cells highlighted
+pub struct Html;
+/// LaTeX `tabular` with conflicting cells in bold
+pub struct Latex;
+
+/// Renderer by name: text, markdown, csv, html or latex
+pub fn renderer(name: &str) -> Result<Box<dyn Renderer>, String> {
+    match name {
+        "text" => Ok(Box::new(Text)),
+        "markdown" => Ok(Box::new(Markdown)),
+        "csv" => Ok(Box::new(Csv)),
+        "html" => Ok(Box::new(Html)),
+        "latex" => Ok(Box::new(Latex)),
+        x => Err(format!(
+            "unknown table format {}, use text, markdown, csv, html or latex",
+            x
+        )),
+    }
+}
+
+struct Cell {
+    // action shown by text renderer, None for error
+    action: Option<String>,
+    // more actions for conflict
+    all: Vec<String>,
+}
+
+impl Cell {
+    fn conflict(&self) -> bool {
+        self.all.len() > 1
+    }
+
+    // actions of conflict joined, empty for error
+    fn text(&self) -> String {
+        match self.conflict() {
+            true => self.all.join("/"),
+            false => self.action.clone().unwrap_or_default(),
+        }
+    }
+}
+
+struct Row {
+    // symbol of state and its number
+    label: String,
+    actions: Vec<Cell>,
+    gotos: Vec<Option<usize>>,
+}
+
+// table read through the trait, shared by renderers
+struct Grid {
+    name: String,
+    lookahead: Vec<Sym>,
+    syms: Vec<char>,
+    rows: Vec<Row>,
+}
+
+impl Grid {
+    fn new(table: &dyn Table) -> Self {
+        let gramm = table.grammar();
+        let lookahead = lookahead(gramm);
+        let syms = sorted(&gramm.terms().union(gramm.non_terms()).copied().collect());
+        let rows = (0..table.states())
+            .map(|s| Row {
+                label: format!("{}{}", Sym::Normal(table.get_char(s).unwrap()), s),
+                actions: lookahead
+                    .iter()
+                    .map(|x| Cell {
+                        action: table.get_action(s, *x).map(|a| a.to_string()),
+                        all: table
+                            .get_actions(s, *x)
+                            .iter()
+                            .map(|a| a.to_string())
+                            .collect(),
+                    })
+                    .collect(),
+                gotos: syms.iter().map(|c| table.get_goto(s, *c)).collect(),
+            })
+            .collect();
+        Self {
+            name: table.name().to_string(),
+            lookahead,
+            syms,
+            rows,
+        }
+    }
+
+    // ACTION with targets of shifts, GOTO over non-terminals,
+    // rows are labeled by state number only
+    fn classic(table: &dyn Table) -> Self {
+        let gramm = table.grammar();
+        let lookahead = lookahead(gramm);
+        let syms = sorted(gramm.non_terms());
+        let rows = (0..table.states())
+            .map(|s| Row {
+                label: s.to_string(),
+                actions: lookahead
+                    .iter()
+                    .map(|x| {
+                        let all: Vec<String> = classic_actions(table, s, *x)
+                            .iter()
+                            .map(|a| a.to_string())
+                            .collect();
+                        Cell {
+                            action: (!all.is_empty()).then(|| all.join("/")),
+                            all,
+                        }
+                    })
+                    .collect(),
+                gotos: syms.iter().map(|c| table.get_goto(s, *c)).collect(),
+            })
+            .collect();
+        Self {
+            name: table.name().to_string(),
+            lookahead,
+            syms,
+            rows,
+        }
+    }
+
+    fn header(&self) -> (Vec<String>, Vec<String>) {
+        (
+            self.lookahead.iter().map(|x| x.to_string()).collect(),
+            self.syms
+                .iter()
+                .map(|c| Sym::Normal(*c).to_string())
+                .collect(),
+        )
+    }
+}
+
+fn goto_text(goto: &Option<usize>) -> String {
+    goto.map(|x| x.to_string()).unwrap_or_default()
+}
+
+impl Renderer for Text {
+    fn render(&self, table: &dyn Table) -> String {
+        self.grid(&Grid::new(table))
+    }
+
+    fn render_classic(&self, table: &dyn Table) -> String {
+        self.grid(&Grid::classic(table))
+    }
+}
+
+impl Text {
+    fn grid(&self, grid: &Grid) -> String {
+        let (lookahead, syms) = grid.header();
+        let mut res = format!("{}\nstate\t|", grid.name);
+        for x in &lookahead {
+            res.push_str(&format!("{}\t", x));
+        }
+        res.push('|');
+        for x in &syms {
+            res.push_str(&format!("{}\t", x));
+        }
+        res.push('\n');
+        for row in &grid.rows {
+            res.push_str(&format!("{}\t|", row.label));
+            for cell in &row.actions {
+                match &cell.action {
+                    Some(a) => res.push_str(&format!("{}\t", a)),
+                    None => res.push_str(" \t"),
+                }
+            }
+            res.push('|');
+            for g in &row.gotos {
+                match g {
+                    Some(g) => res.push_str(&format!("{}\t", g)),
+                    None => res.push_str(" \t"),
+                }
+            }
+            res.push('\n');
+        }
+        res
+    }
+}
+
+impl Renderer for Markdown {
+    fn render(&self, table: &dyn Table) -> String {
+        self.grid(&Grid::new(table))
+    }
+
+    fn render_classic(&self, table: &dyn Table) -> String {
+        self.grid(&Grid::classic(table))
+    }
+}
+
+impl Markdown {
+    fn grid(&self, grid: &Grid) -> String {
+        // symbols that would be taken as markup
+        let escape = |x: &str| -> String {
+            x.chars()
+                .map(|c| match "\\`*_[]<>|#~".contains(c) {
+                    true => format!("\\{}", c),
+                    false => c.to_string(),
+                })
+                .collect()
+        };
+        let (lookahead, syms) = grid.header();
+        let mut res = format!("**{}**\n\n| state |", escape(&grid.name));
+        for x in &lookahead {
+            res.push_str(&format!(" {} |", escape(x)));
+        }
+        // goto columns are prefixed so they differ from terminals
+        for x in &syms {
+            res.push_str(&format!(" goto {} |", escape(x)));
+        }
+        res.push_str("\n|---|");
+        res.push_str(&":---:|".repeat(lookahead.len() + syms.len()));
+        res.push('\n');
+        for row in &grid.rows {
+            res.push_str(&format!("| {} |", escape(&row.label)));
+            for cell in &row.actions {
+                match cell.conflict() {
+                    true => res.push_str(&format!(" **{}** |", escape(&cell.text()))),
+                    false => res.push_str(&format!(" {} |", cell.text())),
+                }
+            }
+            for g in &row.gotos {
+                res.push_str(&format!(" {} |", goto_text(g)));
+            }
+            res.push('\n');
+        }
+        res
+    }
+}
+
+impl Renderer for Csv {
+    fn render(&self, table: &dyn Table) -> String {
+        self.grid(&Grid::new(table))
+    }
+
+    fn render_classic(&self, table: &dyn Table) -> String {
+        self.grid(&Grid::classic(table))
+    }
+}
+
+impl Csv {
+    fn grid(&self, grid: &Grid) -> String {
+        let cell = |x: &str| match x.contains([',', '"', '\n']) {
+            true => format!("\"{}\"", x.replace('"', "\"\"")),
+            false => x.to_string(),
+        };
+        let (lookahead, syms) = grid.header();
+        // goto columns are prefixed so the header stays unique
+        let mut header = vec!["state".to_string()];
+        header.extend(lookahead.iter().map(|x| cell(x)));
+        header.extend(syms.iter().map(|x| cell(&format!("goto {}", x))));
+        let mut res = header.join(",") + "\n";
+        for row in &grid.rows {
+            let mut cols = vec![cell(&row.label)];
+            cols.extend(row.actions.iter().map(|x| cell(&x.text())));
+            cols.extend(row.gotos.iter().map(goto_text));
+            res.push_str(&cols.join(","));
+            res.push('\n');
+        }
+        res
+    }
+}
+
+impl Renderer for Html {
+    fn render(&self, table: &dyn Table) -> String {
+        self.grid(&Grid::new(table))
+    }
+
+    fn render_classic(&self, table: &dyn Table) -> String {
+        self.grid(&Grid::classic(table))
+    }
+}
+
+impl Html {
+    fn grid(&self, grid: &Grid) -> String {
+        let (lookahead, syms) = grid.header();
+        let mut res = format!(
+            "<table class=\"lr-table\">\n<caption>{}</caption>\n",
+            html(&grid.name)
+        );
+        res.push_str(&format!(
+            "<thead>\n<tr><th rowspan=\"2\">state</th><th colspan=\"{}\">action</th><th colspan=\"{}\">goto</th></tr>\n<tr>",
+            lookahead.len(),
+            syms.len()
+        ));
+        for x in lookahead.iter().chain(&syms) {
+            res.push_str(&format!("<th>{}</th>", html(x)));
+        }
+        res.push_str("</tr>\n</thead>\n<tbody>\n");
+        for row in &grid.rows {
+            res.push_str(&format!("<tr><th>{}</th>", html(&row.label)));
+            for cell in &row.actions {
+                match cell.conflict() {
+                    true => res.push_str(&format!(
+                        "<td class=\"conflict\" style=\"background:#f8d7da\">{}</td>",
+                        html(&cell.text())
+                    )),
+                    false => res.push_str(&format!("<td>{}</td>", html(&cell.text()))),
+                }
+            }
+            for g in &row.gotos {
+                res.push_str(&format!("<td>{}</td>", goto_text(g)));
+            }
+            res.push_str("</tr>\n");
+        }
+        res.push_str("</tbody>\n</table>\n");
+        res
+    }
+}
+
+pub(crate) fn html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+impl Renderer for Latex {
+    fn render(&self, table: &dyn Table) -> String {
+        self.grid(&Grid::new(table))
+    }
+
+    fn render_classic(&self, table: &dyn Table) -> String {
+        self.grid(&Grid::classic(table))
+    }
+}
+
+impl Latex {
+    fn grid(&self, grid: &Grid) -> String {
+        let (lookahead, syms) = grid.header();
+        let mut res = format!(
+            "% {}\n\\begin{{tabular}}{{l|{}|{}}}\n",
+            grid.name,
+            "c".repeat(lookahead.len()),
+            "c".repeat(syms.len())
+        );
+        res.push_str(&format!(
+            " & \\multicolumn{{{}}}{{c|}}{{action}} & \\multicolumn{{{}}}{{c}}{{goto}} \\\\\n",
+            lookahead.len(),
+            syms.len()
+        ));
+        let mut header = vec!["state".to_string()];
+        header.extend(lookahead.iter().chain(&syms).map(|x| latex(x)));
+        res.push_str(&format!("{} \\\\\n\\hline\n", header.join(" & ")));
+        for row in &grid.rows {
+            let mut cols = vec![latex(&row.label)];
+            cols.extend(row.actions.iter().map(|x| match x.conflict() {
+                true => format!("\\textbf{{{}}}", latex(&x.text())),
+                false => latex(&x.text()),
+            }));
+            cols.extend(row.gotos.iter().map(goto_text));
+            res.push_str(&format!("{} \\\\\n", cols.join(" & ")));
+        }
+        res.push_str("\\end{tabular}\n");
+        res
+    }
+}
+
+fn latex(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            '\\' => "\\textbackslash{}".to_string(),
+            '~' => "\\textasciitilde{}".to_string(),
+            '^' => "\\textasciicircum{}".to_string(),
+            '<' => "\\textless{}".to_string(),
+            '>' => "\\textgreater{}".to_string(),
+            '&' | '%' | '$' | '#' | '_' | '{' | '}' => format!("\\{}", c),
+            c => c.to_string(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        grammar::Grammar,
+        table::{algo::Algo, classic::ClassicTable},
+    };
+
+    use super::*;
+
+    fn assign(algo: Algo) -> Box<dyn Table> {
+        let g = Grammar::from_text(include_str!("../../examples/assign.gram")).unwrap();
+        algo.build(&g)
+    }
+
+    #[test]
+    fn text() {
+        let table = assign(Algo::LALR1);
+        let text = Text.render(table.as_ref());
+        assert_eq!(text, table.to_string());
+        let mut lines = text.lines();
+        assert_eq!(lines.next(), Some("LALR1Table"));
+        assert_eq!(
+            lines.next(),
+            Some("state\t|*\t=\ta\teps\t|*\t=\tA\tL\tR\tS\ta\t")
+        );
+        // LR1Table keeps empty actions, missing gotos are spaces
+        assert_eq!(lines.next(), Some("S0\t|S\t\tS\t\t|1\t \t5\t6\t9\t \t4\t"));
+    }
+
+    #[test]
+    fn formats() {
+        let table = assign(Algo::SLR1);
+        let md = Markdown.render(table.as_ref());
+        assert!(md.starts_with("**SLR1Table**\n\n| state | \\* | = | a | eps | goto \\* |"));
+        assert!(md.contains("| L6 |  | **S/R5** |"));
+        assert_eq!(md.lines().count(), table.states() + 4);
+
+        let csv = Csv.render(table.as_ref());
+        assert!(csv.starts_with("state,*,=,a,eps,goto *,goto =,goto A,"));
+        assert!(csv.contains("\nL6,,S/R5,,R5,"));
+
+        let html = Html.render(table.as_ref());
+        assert!(html.contains("<td class=\"conflict\" style=\"background:#f8d7da\">S/R5</td>"));
+        // two header rows
+        assert_eq!(html.matches("<tr>").count(), table.states() + 2);
+
+        let latex = Latex.render(table.as_ref());
+        assert!(latex.contains(
+            "\\begin{tabular}{l|cccc|ccccccc}\n & \\multicolumn{4}{c|}{action} & \\multicolumn{7}{c}{goto} \\\\\n"
+        ));
+        assert!(latex.contains("\nL6 &  & \\textbf{S/R5} &  & R5 & "));
+        assert!(latex.ends_with("\\end{tabular}\n"));
+
+        // ACTION/GOTO layout in every format
+        let classic = ClassicTable::new(table.as_ref());
+        assert_eq!(Text.render_classic(table.as_ref()), classic.to_string());
+        assert!(classic.to_string().contains("\n6\t| \ts7/r5\t \tr5\t|"));
+        let md = Markdown.render_classic(table.as_ref());
+        assert!(md.contains("| goto A | goto L | goto R | goto S |\n"));
+        assert!(md.contains("| 6 |  | **s7/r5** |  | r5 |"));
+        let latex = Latex.render_classic(table.as_ref());
+        assert!(latex.contains("\n6 &  & \\textbf{s7/r5} &  & r5 & "));
+        assert!(Html.render_classic(table.as_ref()).contains(">s7/r5</td>"));
+
+        assert!(renderer("latex").is_ok());
+        assert!(renderer("pdf").is_err());
+    }
+}
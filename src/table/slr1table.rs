@@ -7,68 +7,40 @@ use crate::{
         lrnode::LRNode,
         rule::LRRule,
     },
-    table::lrtable::{Action, Table},
+    table::{
+        lrtable::{cell_actions, Action, Table},
+        render::{Renderer, Text},
+    },
 };
 
-pub(crate) struct SLR1Table<'a> {
+pub(crate) struct SLR1Table {
     action: Vec<(char, HashMap<Sym, Action>)>,
     goto: Vec<HashMap<char, usize>>,
-    syms: Vec<char>,
-    gramm: &'a Grammar,
+    conflicts: Vec<HashMap<Sym, Vec<Action>>>,
+    gramm: Grammar,
 }
 
-impl Display for SLR1Table<'_> {
+impl Display for SLR1Table {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        writeln!(f, "SLR1Table")?;
-        write!(f, "state\t|")?;
-        for c in self.syms.iter().filter(|x| self.gramm.is_term(x)) {
-            write!(f, "{}\t", c)?;
-        }
-        write!(f, "{}\t|", Sym::Eps)?;
-        for c in &self.syms {
-            write!(f, "{}\t", c)?;
-        }
-        writeln!(f, "")?;
-        for i in 0..self.action.len() {
-            let (c, _) = &self.action[i];
-            write!(f, "{}{}\t|", c, i)?;
-            let (_, a) = &self.action[i];
-            for c in self.syms.iter().filter(|x| self.gramm.is_term(x)) {
-                match a.get(&Sym::Normal(*c)) {
-                    Some(a) => write!(f, "{}\t", a),
-                    None => write!(f, " \t"),
-                }?;
-            }
-            match a.get(&Sym::Eps) {
-                Some(a) => write!(f, "{}\t|", a),
-                None => write!(f, " \t|"),
-            }?;
-            for s in &self.syms {
-                match self.goto[i].get(s) {
-                    Some(g) => write!(f, "{}\t", g),
-                    None => write!(f, " \t"),
-                }?;
-            }
-            writeln!(f, "")?;
-        }
-        Ok(())
+        f.write_str(&Text.render(self))
     }
 }
 
-impl<'a> SLR1Table<'a> {
-    pub(crate) fn new(graph: LR0Graph, gramm: &'a Grammar) -> Self {
+impl SLR1Table {
+    pub(crate) fn new(graph: LR0Graph, gramm: &Grammar) -> Self {
         let mut syms: Vec<char> = vec![];
         gramm
             .terms
             .union(&gramm.non_terms)
             .for_each(|x| syms.push(*x));
-        let action: Vec<(char, HashMap<Sym, Action>)> = graph
+        let (action, conflicts): (Vec<_>, Vec<_>) = graph
             .nodes()
             .iter()
             .map(|x| {
                 let mut res =
                     HashMap::from_iter(syms.iter().map(|x| (Sym::Normal(*x), Action::Empty)));
                 res.insert(Sym::Eps, Action::Empty);
+                let mut all: HashMap<Sym, Vec<Action>> = HashMap::new();
                 for r in x.all_rules() {
                     match r.get_sym(gramm) {
                         Some(s) => {
@@ -77,29 +49,32 @@ impl<'a> SLR1Table<'a> {
                             }
                             let tmp = res.get(&Sym::Normal(s)).unwrap().update(Action::Shift);
                             res.insert(Sym::Normal(s), tmp);
+                            Action::Shift.push_into(all.entry(Sym::Normal(s)).or_default());
                         }
                         None => {
                             for f in gramm.follow(r.get_left(gramm).unwrap()) {
-                                let tmp = res.get(&f).unwrap().update(Action::Reduction(r.rule));
-                                res.insert(f.clone(), tmp);
+                                let tmp = res.get(f).unwrap().update(Action::Reduction(r.rule));
+                                res.insert(*f, tmp);
+                                Action::Reduction(r.rule).push_into(all.entry(*f).or_default());
                             }
                         }
                     }
                 }
-                (x.from(), res)
+                all.retain(|_, v| v.len() > 1);
+                ((x.from(), res), all)
             })
-            .collect();
+            .unzip();
 
         Self {
             action,
             goto: graph.edges().to_owned(),
-            syms,
-            gramm,
+            conflicts,
+            gramm: gramm.clone(),
         }
     }
 }
 
-impl Table for SLR1Table<'_> {
+impl Table for SLR1Table {
     fn get_action(&self, state: usize, sym: Sym) -> Option<Action> {
         let (_, a) = self.action.get(state)?;
         a.get(&sym).copied()
@@ -113,4 +88,23 @@ impl Table for SLR1Table<'_> {
         let (c, _) = self.action.get(state)?;
         Some(*c)
     }
+
+    fn states(&self) -> usize {
+        self.action.len()
+    }
+
+    fn grammar(&self) -> &Grammar {
+        &self.gramm
+    }
+
+    fn name(&self) -> &str {
+        "SLR1Table"
+    }
+
+    fn get_actions(&self, state: usize, sym: Sym) -> Vec<Action> {
+        match self.conflicts.get(state).and_then(|x| x.get(&sym)) {
+            Some(all) => all.clone(),
+            None => cell_actions(self.get_action(state, sym)),
+        }
+    }
 }
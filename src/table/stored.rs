@@ -0,0 +1,568 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Display,
+};
+
+use crate::{
+    grammar::{Grammar, Sym},
+    json::{self, Value},
+    table::{
+        lrtable::{lookahead, sorted, Action, Table},
+        render::{Renderer, Text},
+    },
+};
+
+const FORMAT: &str = "lr0_gen table";
+const MAGIC: &[u8; 4] = b"LR0T";
+const VERSION: u64 = 1;
+// end of input in binary format
+const EPS: u32 = u32::MAX;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct State {
+    sym: char,
+    // every action of the cell, more of them for conflict
+    actions: HashMap<Sym, Vec<Action>>,
+    goto: HashMap<char, usize>,
+}
+
+/// Table copied out of any other table, it can be written as JSON or
+/// compact binary and loaded back without building the automaton.
+/// Grammar is rebuilt from the stored symbols and rules.
+#[derive(Debug, Clone)]
+pub struct StoredTable {
+    name: String,
+    gramm: Grammar,
+    states: Vec<State>,
+}
+
+impl StoredTable {
+    pub fn new(table: &dyn Table) -> Self {
+        let gramm = table.grammar().clone();
+        let lookahead = lookahead(&gramm);
+        let syms = sorted(&gramm.terms().union(gramm.non_terms()).copied().collect());
+        let states = (0..table.states())
+            .map(|s| State {
+                sym: table.get_char(s).unwrap(),
+                actions: lookahead
+                    .iter()
+                    .map(|x| (*x, table.get_actions(s, *x)))
+                    .filter(|(_, a)| !a.is_empty())
+                    .collect(),
+                goto: syms
+                    .iter()
+                    .filter_map(|c| Some((*c, table.get_goto(s, *c)?)))
+                    .collect(),
+            })
+            .collect();
+        Self {
+            name: table.name().to_string(),
+            gramm,
+            states,
+        }
+    }
+
+    /// Hash of the grammar the table was built from, see [`Grammar::fingerprint`]
+    pub fn grammar_hash(&self) -> u64 {
+        self.gramm.fingerprint()
+    }
+
+    /// JSON or binary format recognized by the first bytes
+    pub fn load(bytes: &[u8]) -> Result<Self, String> {
+        if bytes.starts_with(MAGIC) {
+            return Self::from_bytes(bytes);
+        }
+        match std::str::from_utf8(bytes) {
+            Ok(text) => Self::from_json(text),
+            Err(_) => Err("table is neither JSON nor binary".to_string()),
+        }
+    }
+
+    /// True when bytes look like stored table and not like grammar
+    pub fn is_stored(bytes: &[u8]) -> bool {
+        if bytes.starts_with(MAGIC) {
+            return true;
+        }
+        // format field decides, so tables reformatted by hand are found too
+        let v = std::str::from_utf8(bytes)
+            .ok()
+            .and_then(|x| json::parse(x).ok());
+        v.as_ref()
+            .and_then(|x| x.get("format"))
+            .and_then(Value::as_str)
+            == Some(FORMAT)
+    }
+
+    /// Symbols are one character strings, end of input is empty string
+    pub fn to_json(&self) -> String {
+        let chr = |c: &char| json::string(&c.to_string());
+        let states = self.states.iter().map(|s| {
+            let actions = lookahead(&self.gramm).into_iter().filter_map(|x| {
+                let all = s.actions.get(&x)?;
+                let key = match x {
+                    Sym::Normal(c) => c.to_string(),
+                    Sym::Eps => String::new(),
+                };
+                let all = all.iter().map(|a| json::string(&action_text(a)));
+                Some((key, json::array(all)))
+            });
+            let actions: Vec<(String, String)> = actions.collect();
+            let mut goto: Vec<(&char, &usize)> = s.goto.iter().collect();
+            goto.sort();
+            let goto: Vec<(String, String)> = goto
+                .into_iter()
+                .map(|(c, t)| (c.to_string(), t.to_string()))
+                .collect();
+            json::object([
+                ("sym", chr(&s.sym)),
+                (
+                    "actions",
+                    json::object(actions.iter().map(|(k, v)| (k.as_str(), v.clone()))),
+                ),
+                (
+                    "goto",
+                    json::object(goto.iter().map(|(k, v)| (k.as_str(), v.clone()))),
+                ),
+            ])
+        });
+        let rules = self.gramm.rules().iter().map(|r| {
+            json::object([
+                ("left", chr(&r.left())),
+                ("right", json::string(&r.right().iter().collect::<String>())),
+            ])
+        });
+        json::object([
+            ("format", json::string(FORMAT)),
+            ("version", VERSION.to_string()),
+            ("name", json::string(&self.name)),
+            (
+                "grammar_hash",
+                json::string(&format!("{:016x}", self.grammar_hash())),
+            ),
+            (
+                "non_terms",
+                json::array(sorted(self.gramm.non_terms()).iter().map(chr)),
+            ),
+            (
+                "terms",
+                json::array(sorted(self.gramm.terms()).iter().map(chr)),
+            ),
+            ("rules", json::array(rules)),
+            ("states", json::array(states)),
+        ])
+    }
+
+    pub fn from_json(text: &str) -> Result<Self, String> {
+        let v = json::parse(text)?;
+        let field = |v: &'_ Value, key: &str| -> Result<Value, String> {
+            v.get(key).cloned().ok_or(format!("missing {}", key))
+        };
+        let bad = |what: &str| format!("invalid {}", what);
+        let chr = |v: &Value| -> Result<char, String> {
+            let s = v.as_str().ok_or(bad("symbol"))?;
+            let mut chars = s.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => Ok(c),
+                _ => Err(bad("symbol")),
+            }
+        };
+        let chars = |v: Value| -> Result<Vec<char>, String> {
+            v.as_array()
+                .ok_or(bad("symbols"))?
+                .iter()
+                .map(chr)
+                .collect()
+        };
+
+        if field(&v, "format")?.as_str() != Some(FORMAT) {
+            return Err("not a stored table".to_string());
+        }
+        check_version(field(&v, "version")?.as_u64())?;
+        let name = field(&v, "name")?.as_str().ok_or(bad("name"))?.to_string();
+        let hash = field(&v, "grammar_hash")?;
+        let hash =
+            u64::from_str_radix(hash.as_str().ok_or(bad("hash"))?, 16).map_err(|_| bad("hash"))?;
+        let non_terms = chars(field(&v, "non_terms")?)?;
+        let terms = chars(field(&v, "terms")?)?;
+        let mut rules = vec![];
+        for r in field(&v, "rules")?.as_array().ok_or(bad("rules"))? {
+            let left = chr(&field(r, "left")?)?;
+            let right = field(r, "right")?;
+            rules.push((left, right.as_str().ok_or(bad("rule"))?.chars().collect()));
+        }
+
+        let mut states = vec![];
+        for s in field(&v, "states")?.as_array().ok_or(bad("states"))? {
+            let mut actions = HashMap::new();
+            for (k, all) in field(s, "actions")?.as_object().ok_or(bad("actions"))? {
+                let sym = match k.chars().count() {
+                    0 => Sym::Eps,
+                    1 => Sym::Normal(k.chars().next().unwrap()),
+                    _ => return Err(bad("symbol")),
+                };
+                let all = all.as_array().ok_or(bad("actions"))?.iter();
+                let all = all.map(|a| parse_action(a.as_str().ok_or(bad("action"))?));
+                actions.insert(sym, all.collect::<Result<Vec<Action>, String>>()?);
+            }
+            let mut goto = HashMap::new();
+            for (k, t) in field(s, "goto")?.as_object().ok_or(bad("goto"))? {
+                let c = chr(&Value::String(k.clone()))?;
+                goto.insert(c, t.as_u64().ok_or(bad("goto"))? as usize);
+            }
+            states.push(State {
+                sym: chr(&field(s, "sym")?)?,
+                actions,
+                goto,
+            });
+        }
+        Self::build(name, hash, non_terms, terms, rules, states)
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut res = MAGIC.to_vec();
+        res.extend((VERSION as u16).to_le_bytes());
+        let put = |x: u32, res: &mut Vec<u8>| res.extend(x.to_le_bytes());
+        put(self.name.len() as u32, &mut res);
+        res.extend(self.name.as_bytes());
+        res.extend(self.grammar_hash().to_le_bytes());
+        for set in [self.gramm.non_terms(), self.gramm.terms()] {
+            let set = sorted(set);
+            put(set.len() as u32, &mut res);
+            set.iter().for_each(|c| put(*c as u32, &mut res));
+        }
+        put(self.gramm.rules().len() as u32, &mut res);
+        for r in self.gramm.rules() {
+            put(r.left() as u32, &mut res);
+            put(r.right().len() as u32, &mut res);
+            r.right().iter().for_each(|c| put(*c as u32, &mut res));
+        }
+        put(self.states.len() as u32, &mut res);
+        for s in &self.states {
+            put(s.sym as u32, &mut res);
+            put(s.actions.len() as u32, &mut res);
+            for x in lookahead(&self.gramm) {
+                let all = match s.actions.get(&x) {
+                    Some(all) => all,
+                    None => continue,
+                };
+                match x {
+                    Sym::Normal(c) => put(c as u32, &mut res),
+                    Sym::Eps => put(EPS, &mut res),
+                }
+                put(all.len() as u32, &mut res);
+                for a in all {
+                    match a {
+                        Action::Shift => put(0, &mut res),
+                        Action::Accept => put(1, &mut res),
+                        Action::Reduction(r) => put(2 + *r as u32, &mut res),
+                        Action::Error | Action::Empty => unreachable!(),
+                    }
+                }
+            }
+            let mut goto: Vec<(&char, &usize)> = s.goto.iter().collect();
+            goto.sort();
+            put(goto.len() as u32, &mut res);
+            for (c, t) in goto {
+                put(*c as u32, &mut res);
+                put(*t as u32, &mut res);
+            }
+        }
+        res
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
+        if !bytes.starts_with(MAGIC) || bytes.len() < 6 {
+            return Err("not a stored table".to_string());
+        }
+        check_version(Some(u16::from_le_bytes([bytes[4], bytes[5]]) as u64))?;
+        let mut reader = Reader { bytes, place: 6 };
+
+        let len = reader.u32()? as usize;
+        let name = String::from_utf8(reader.take(len)?.to_vec()).map_err(|_| reader.error())?;
+        let hash = u64::from_le_bytes(reader.take(8)?.try_into().unwrap());
+        let non_terms = reader.chars()?;
+        let terms = reader.chars()?;
+        let mut rules = vec![];
+        for _ in 0..reader.u32()? {
+            rules.push((reader.char()?, reader.chars()?));
+        }
+        let mut states = vec![];
+        for _ in 0..reader.u32()? {
+            let sym = reader.char()?;
+            let mut actions = HashMap::new();
+            for _ in 0..reader.u32()? {
+                let x = match reader.u32()? {
+                    EPS => Sym::Eps,
+                    c => Sym::Normal(char::from_u32(c).ok_or(reader.error())?),
+                };
+                let mut all = vec![];
+                for _ in 0..reader.u32()? {
+                    all.push(match reader.u32()? {
+                        0 => Action::Shift,
+                        1 => Action::Accept,
+                        r => Action::Reduction(r as usize - 2),
+                    });
+                }
+                actions.insert(x, all);
+            }
+            let mut goto = HashMap::new();
+            for _ in 0..reader.u32()? {
+                goto.insert(reader.char()?, reader.u32()? as usize);
+            }
+            states.push(State { sym, actions, goto });
+        }
+        if reader.place != bytes.len() {
+            return Err(reader.error());
+        }
+        Self::build(name, hash, non_terms, terms, rules, states)
+    }
+
+    fn build(
+        name: String,
+        hash: u64,
+        non_terms: Vec<char>,
+        terms: Vec<char>,
+        rules: Vec<(char, Vec<char>)>,
+        states: Vec<State>,
+    ) -> Result<Self, String> {
+        let mut gramm = Grammar::new(HashSet::from_iter(non_terms), HashSet::from_iter(terms));
+        for (left, right) in rules {
+            gramm.add_rule_vec(left, right)?;
+        }
+        if gramm.rules().is_empty() {
+            return Err("table without rules".to_string());
+        }
+        gramm.update_sets();
+        if gramm.fingerprint() != hash {
+            return Err("grammar hash does not match".to_string());
+        }
+        // targets and rules must exist so table can not make parser panic
+        for s in &states {
+            let rule_ok = |a: &Action| match a {
+                Action::Reduction(r) => *r < gramm.rules().len(),
+                _ => true,
+            };
+            if s.goto.values().any(|t| *t >= states.len())
+                || !s.actions.values().flatten().all(rule_ok)
+            {
+                return Err("table refers to missing state or rule".to_string());
+            }
+            // missing symbol is an error, empty list would be a conflict
+            if s.actions.values().any(|x| x.is_empty()) {
+                return Err("table has empty list of actions".to_string());
+            }
+        }
+        Ok(Self {
+            name,
+            gramm,
+            states,
+        })
+    }
+}
+
+fn check_version(version: Option<u64>) -> Result<(), String> {
+    match version {
+        Some(VERSION) => Ok(()),
+        Some(x) => Err(format!("unsupported table version {}", x)),
+        None => Err("invalid version".to_string()),
+    }
+}
+
+fn action_text(action: &Action) -> String {
+    match action {
+        Action::Shift => "s".to_string(),
+        Action::Accept => "acc".to_string(),
+        Action::Reduction(r) => format!("r{}", r),
+        Action::Error | Action::Empty => unreachable!(),
+    }
+}
+
+fn parse_action(text: &str) -> Result<Action, String> {
+    match text {
+        "s" => Ok(Action::Shift),
+        "acc" => Ok(Action::Accept),
+        x => match x.strip_prefix('r').and_then(|r| r.parse().ok()) {
+            Some(r) => Ok(Action::Reduction(r)),
+            None => Err(format!("invalid action {}", x)),
+        },
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    place: usize,
+}
+
+impl Reader<'_> {
+    fn error(&self) -> String {
+        format!("invalid binary table at byte {}", self.place)
+    }
+
+    fn take(&mut self, len: usize) -> Result<&[u8], String> {
+        let res = self
+            .bytes
+            .get(self.place..self.place + len)
+            .ok_or(self.error())?;
+        self.place += len;
+        Ok(res)
+    }
+
+    fn u32(&mut self) -> Result<u32, String> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn char(&mut self) -> Result<char, String> {
+        let x = self.u32()?;
+        char::from_u32(x).ok_or(self.error())
+    }
+
+    fn chars(&mut self) -> Result<Vec<char>, String> {
+        (0..self.u32()?).map(|_| self.char()).collect()
+    }
+}
+
+impl Display for StoredTable {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&Text.render(self))
+    }
+}
+
+impl Table for StoredTable {
+    fn get_action(&self, state: usize, sym: Sym) -> Option<Action> {
+        let all = self.states.get(state)?.actions.get(&sym)?;
+        match all.len() {
+            1 => Some(all[0]),
+            _ => Some(Action::Error),
+        }
+    }
+
+    fn get_goto(&self, state: usize, chr: char) -> Option<usize> {
+        self.states.get(state)?.goto.get(&chr).copied()
+    }
+
+    fn get_char(&self, state: usize) -> Option<char> {
+        Some(self.states.get(state)?.sym)
+    }
+
+    fn states(&self) -> usize {
+        self.states.len()
+    }
+
+    fn grammar(&self) -> &Grammar {
+        &self.gramm
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn get_actions(&self, state: usize, sym: Sym) -> Vec<Action> {
+        match self.states.get(state).and_then(|x| x.actions.get(&sym)) {
+            Some(all) => all.clone(),
+            None => vec![],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{earley::Earley, stackautomata::StackAutomata, table::algo::Algo};
+
+    use super::*;
+
+    fn same(a: &dyn Table, b: &dyn Table) {
+        let g = a.grammar();
+        let syms: Vec<char> = g.terms().union(g.non_terms()).copied().collect();
+        assert_eq!(a.states(), b.states());
+        for s in 0..a.states() {
+            assert_eq!(a.get_char(s), b.get_char(s));
+            for c in &syms {
+                assert_eq!(a.get_goto(s, *c), b.get_goto(s, *c));
+            }
+            for c in g.terms() {
+                assert_eq!(
+                    a.get_actions(s, Sym::Normal(*c)),
+                    b.get_actions(s, Sym::Normal(*c))
+                );
+            }
+            assert_eq!(a.get_actions(s, Sym::Eps), b.get_actions(s, Sym::Eps));
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let files = [
+            include_str!("../../examples/example.gram"),
+            include_str!("../../examples/assign.gram"),
+            include_str!("../../examples/recovery.gram"),
+            include_str!("../../examples/simple.gram"),
+        ];
+        for file in files {
+            let g = Grammar::from_text(file).unwrap();
+            for algo in [Algo::LR0, Algo::SLR1, Algo::LALR1, Algo::LR1] {
+                let table = algo.build(&g);
+                let stored = StoredTable::new(table.as_ref());
+                let json = StoredTable::load(stored.to_json().as_bytes()).unwrap();
+                let binary = StoredTable::load(&stored.to_bytes()).unwrap();
+                same(table.as_ref(), &json);
+                same(table.as_ref(), &binary);
+                assert_eq!(json.name(), table.name());
+                assert_eq!(binary.grammar_hash(), g.fingerprint());
+            }
+        }
+    }
+
+    #[test]
+    fn parse_without_grammar() {
+        let bytes = {
+            let g = Grammar::from_text(include_str!("../../examples/example.gram")).unwrap();
+            StoredTable::new(Algo::LALR1.build(&g).as_ref()).to_bytes()
+        };
+        let table = StoredTable::load(&bytes).unwrap();
+        for input in ["a+a*(a)", "(a+", "a*a+a"] {
+            let res = StackAutomata::new(&table, input).run();
+            assert_eq!(res.is_ok(), Earley::new(table.grammar(), input).accepts());
+        }
+        let tree = StackAutomata::new(&table, "a*a").run().unwrap();
+        assert_eq!(tree.text(), "a*a");
+    }
+
+    #[test]
+    fn invalid() {
+        let g = Grammar::from_text(include_str!("../../examples/simple.gram")).unwrap();
+        let stored = StoredTable::new(Algo::LR1.build(&g).as_ref());
+
+        let json = stored
+            .to_json()
+            .replace("\"right\":\"aAb\"", "\"right\":\"ab\"");
+        assert_eq!(
+            StoredTable::load(json.as_bytes()).err(),
+            Some("grammar hash does not match".to_string())
+        );
+        let json = stored.to_json().replace("\"version\":1", "\"version\":7");
+        assert_eq!(
+            StoredTable::load(json.as_bytes()).err(),
+            Some("unsupported table version 7".to_string())
+        );
+        let json = stored.to_json().replacen("[\"s\"]", "[]", 1);
+        assert_eq!(
+            StoredTable::load(json.as_bytes()).err(),
+            Some("table has empty list of actions".to_string())
+        );
+        let bytes = stored.to_bytes();
+        assert!(StoredTable::load(&bytes[..bytes.len() - 1]).is_err());
+        assert!(StoredTable::load(b"S A\na b\nS->A\n").is_err());
+        assert!(StoredTable::is_stored(&bytes));
+        assert!(StoredTable::is_stored(stored.to_json().as_bytes()));
+        assert!(!StoredTable::is_stored(b"S A\na b\nS->A\n"));
+        let pretty = stored
+            .to_json()
+            .replace(",\"", ",\n  \"")
+            .replacen('{', "{\n  ", 1);
+        assert!(StoredTable::is_stored(format!("\n{}", pretty).as_bytes()));
+        assert!(StoredTable::load(pretty.as_bytes()).is_ok());
+        assert!(!StoredTable::is_stored(
+            b"{\"format\": \"lr0_gen analysis\"}"
+        ));
+    }
+}
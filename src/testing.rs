@@ -0,0 +1,61 @@
+// Grammars, inputs and comparisons shared by tests of tables,
+// reports and emitters
+
+use crate::{
+    grammar::{Grammar, ERROR},
+    stackautomata::StackAutomata,
+    table::{algo::Algo, lrtable::Table},
+};
+
+pub(crate) const GRAMMARS: [(&str, &str); 5] = [
+    ("assign", include_str!("../examples/assign.gram")),
+    ("basic", include_str!("../examples/basic.gram")),
+    ("example", include_str!("../examples/example.gram")),
+    ("recovery", include_str!("../examples/recovery.gram")),
+    ("simple", include_str!("../examples/simple.gram")),
+];
+
+pub(crate) const ALGOS: [Algo; 4] = [Algo::LR0, Algo::SLR1, Algo::LALR1, Algo::LR1];
+
+// table of every grammar by every algorithm, named `grammar_algo`
+pub(crate) fn tables() -> impl Iterator<Item = (String, Algo, Box<dyn Table>)> {
+    GRAMMARS.into_iter().flat_map(|(name, text)| {
+        let g = Grammar::from_text(text).unwrap();
+        ALGOS
+            .into_iter()
+            .map(move |algo| (format!("{}_{}", name, algo), algo, algo.build(&g)))
+    })
+}
+
+// every string of terminals up to length 4
+pub(crate) fn inputs(g: &Grammar) -> Vec<String> {
+    let mut terms: Vec<char> = g.terms().iter().copied().filter(|x| *x != ERROR).collect();
+    terms.sort();
+    let mut res = vec![String::new()];
+    let mut last = vec![String::new()];
+    for _ in 0..4 {
+        last = last
+            .iter()
+            .flat_map(|x| terms.iter().map(move |c| format!("{}{}", x, c)))
+            .collect();
+        res.extend(last.iter().cloned());
+    }
+    res
+}
+
+// both tables give the same tree or fail at the same offset
+// on every input of the grammar of `a`
+pub(crate) fn assert_same_parses(a: &dyn Table, b: &dyn Table) {
+    for input in inputs(a.grammar()) {
+        let x = StackAutomata::new(a, &input).run();
+        let y = StackAutomata::new(b, &input).run();
+        assert_eq!(
+            x.map(|t| t.sexp()).map_err(|e| e.offset),
+            y.map(|t| t.sexp()).map_err(|e| e.offset),
+            "{} and {} differ on {:?}",
+            a.name(),
+            b.name(),
+            input
+        );
+    }
+}
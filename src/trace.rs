@@ -0,0 +1,168 @@
+use std::fmt::Display;
+
+use crate::{grammar::Sym, json};
+
+/// What StackAutomata did in one step
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TraceAction {
+    Shift(usize),
+    Reduce {
+        rule: usize,
+        text: String,
+        goto: usize,
+    },
+    Accept,
+    Error,
+}
+
+impl Display for TraceAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TraceAction::Shift(s) => write!(f, "shift {}", s),
+            TraceAction::Reduce { rule, text, goto } => {
+                write!(f, "reduce {} ({}), goto {}", rule, text, goto)
+            }
+            TraceAction::Accept => write!(f, "accept"),
+            TraceAction::Error => write!(f, "error"),
+        }
+    }
+}
+
+/// Configuration of automata before the step and the step itself
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceStep {
+    pub states: Vec<usize>,
+    pub symbols: Vec<char>,
+    pub input: Vec<char>,
+    pub action: TraceAction,
+}
+
+impl TraceStep {
+    fn columns(&self, step: usize) -> [String; 5] {
+        let states: Vec<String> = self.states.iter().map(|x| x.to_string()).collect();
+        let symbols: Vec<String> = self
+            .symbols
+            .iter()
+            .map(|x| Sym::Normal(*x).to_string())
+            .collect();
+        let input: String = self.input.iter().collect();
+        [
+            step.to_string(),
+            states.join(" "),
+            symbols.join(" "),
+            input + "$",
+            self.action.to_string(),
+        ]
+    }
+}
+
+// one line for stepping through parse
+impl Display for TraceStep {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let [_, states, symbols, input, action] = self.columns(0);
+        write!(
+            f,
+            "states : {} | symbols : {} | input : {} | {}",
+            states, symbols, input, action
+        )
+    }
+}
+
+const HEADER: [&str; 5] = ["step", "states", "symbols", "input", "action"];
+
+#[derive(Debug, Default)]
+pub struct Trace {
+    pub steps: Vec<TraceStep>,
+}
+
+// aligned table
+impl Display for Trace {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let rows: Vec<[String; 5]> = self
+            .steps
+            .iter()
+            .enumerate()
+            .map(|(i, x)| x.columns(i))
+            .collect();
+        let mut widths = HEADER.map(|x| x.chars().count());
+        for r in &rows {
+            for i in 0..widths.len() {
+                widths[i] = widths[i].max(r[i].chars().count());
+            }
+        }
+        let line = |f: &mut std::fmt::Formatter<'_>, cols: Vec<&str>| {
+            let cells: Vec<String> = cols
+                .iter()
+                .zip(widths)
+                .enumerate()
+                .map(|(i, (c, w))| match i {
+                    // input is aligned to the right as in textbooks
+                    3 => format!("{:>w$}", c, w = w),
+                    _ => format!("{:<w$}", c, w = w),
+                })
+                .collect();
+            writeln!(f, "{}", cells.join(" | ").trim_end())
+        };
+        line(f, HEADER.to_vec())?;
+        let sep: Vec<String> = widths.iter().map(|w| "-".repeat(*w)).collect();
+        writeln!(f, "{}", sep.join("-+-"))?;
+        for r in &rows {
+            line(f, r.iter().map(|x| x.as_str()).collect())?;
+        }
+        Ok(())
+    }
+}
+
+impl Trace {
+    pub(crate) fn push(&mut self, step: TraceStep) {
+        self.steps.push(step);
+    }
+
+    pub fn to_csv(&self) -> String {
+        let cell = |x: &str| match x.contains([',', '"', '\n']) {
+            true => format!("\"{}\"", x.replace('"', "\"\"")),
+            false => x.to_string(),
+        };
+        let mut res = HEADER.join(",") + "\n";
+        for (i, s) in self.steps.iter().enumerate() {
+            let cols: Vec<String> = s.columns(i).iter().map(|x| cell(x)).collect();
+            res.push_str(&cols.join(","));
+            res.push('\n');
+        }
+        res
+    }
+
+    pub fn to_json(&self) -> String {
+        json::array(self.steps.iter().map(|s| {
+            let action = match &s.action {
+                TraceAction::Shift(x) => {
+                    json::object([("kind", json::string("shift")), ("state", x.to_string())])
+                }
+                TraceAction::Reduce { rule, text, goto } => json::object([
+                    ("kind", json::string("reduce")),
+                    ("rule", rule.to_string()),
+                    ("text", json::string(text)),
+                    ("goto", goto.to_string()),
+                ]),
+                TraceAction::Accept => json::object([("kind", json::string("accept"))]),
+                TraceAction::Error => json::object([("kind", json::string("error"))]),
+            };
+            json::object([
+                (
+                    "states",
+                    json::array(s.states.iter().map(|x| x.to_string())),
+                ),
+                (
+                    "symbols",
+                    json::array(
+                        s.symbols
+                            .iter()
+                            .map(|x| json::string(&Sym::Normal(*x).to_string())),
+                    ),
+                ),
+                ("input", json::string(&s.input.iter().collect::<String>())),
+                ("action", action),
+            ])
+        }))
+    }
+}